@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+use crate::smoothing::smoothing_coefficient;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AgcConfig {
+    pub enabled: bool,
+    // Level the tracked percentile is steered towards, in bar units.
+    pub target: f32,
+    // Which bar of each frame is tracked: 1.0 follows the loudest bar, 0.9 the
+    // 90th percentile, and so on.
+    pub percentile: f32,
+    pub attack_ms: f32,
+    pub release_ms: f32,
+    pub max_gain: f32,
+}
+
+impl Default for AgcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target: 0.9,
+            percentile: 0.95,
+            attack_ms: 50.0,
+            release_ms: 2000.0,
+            max_gain: 8.0,
+        }
+    }
+}
+
+impl AgcConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.target > 0.0 && self.target <= 1.5) {
+            return Err(format!(
+                "agc target must be in (0, 1.5], got {}",
+                self.target
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.percentile) {
+            return Err(format!(
+                "agc percentile must be between 0 and 1, got {}",
+                self.percentile
+            ));
+        }
+        if !(self.attack_ms > 0.0 && self.release_ms > 0.0) {
+            return Err("agc attackMs and releaseMs must be positive".to_string());
+        }
+        if !(self.max_gain >= 1.0 && self.max_gain.is_finite()) {
+            return Err(format!(
+                "agc maxGain must be at least 1, got {}",
+                self.max_gain
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub struct AutoGain {
+    config: AgcConfig,
+    gain: f32,
+}
+
+impl AutoGain {
+    pub fn new(config: AgcConfig) -> Self {
+        Self { config, gain: 1.0 }
+    }
+
+    // Updates the gain from one frame of ungained bar values and returns it.
+    // Gain may drop below unity (down to 1 / maxGain) to keep loud sources
+    // from pinning every bar.
+    pub fn update(&mut self, bars: &[f32], frame_ms: f32) -> f32 {
+        if !self.config.enabled {
+            self.gain = 1.0;
+            return self.gain;
+        }
+        if bars.is_empty() {
+            return self.gain;
+        }
+
+        let mut sorted = bars.to_vec();
+        sorted.sort_by(f32::total_cmp);
+        let index = ((sorted.len() - 1) as f32 * self.config.percentile).round() as usize;
+        let level = sorted[index];
+
+        let max_gain = self.config.max_gain;
+        let desired = (self.config.target / level.max(1e-3)).clamp(1.0 / max_gain, max_gain);
+
+        let time_ms = if desired < self.gain {
+            self.config.attack_ms
+        } else {
+            self.config.release_ms
+        };
+        self.gain += (desired - self.gain) * smoothing_coefficient(time_ms, frame_ms);
+        self.gain
+    }
+}
@@ -0,0 +1,398 @@
+use serde::Serialize;
+
+use crate::agc::AutoGain;
+use crate::chord::{ChordFrame, ChordTracker};
+use crate::chroma::{ChromaFrame, ChromaTracker};
+use crate::config::{AnalyzerConfig, ChannelMode};
+use crate::features::SpectralFeatures;
+use crate::levels::{LevelMeter, LevelsFrame};
+use crate::loudness::{LoudnessFrame, LoudnessMeter};
+use crate::onset::{BeatEvent, OnsetDetector};
+use crate::pitch::{self, PitchFrame};
+use crate::processor::{AudioProcessor, BarFrame};
+use crate::ring::SlidingWindow;
+use crate::scale::Band;
+use crate::scope::{self, WaveformFrame};
+use crate::spectrogram::{Spectrogram, SpectrogramRow, SpectrogramSnapshot};
+use crate::stereo::{StereoFrame, StereoMeter};
+use crate::tempo::{TempoFrame, TempoTracker};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelSpectrum {
+    pub label: String,
+    pub bars: Vec<f32>,
+    pub peaks: Vec<f32>,
+    pub gain: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpectrumFrame {
+    pub bands: Vec<Band>,
+    pub channels: Vec<ChannelSpectrum>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelFeatures {
+    pub label: String,
+    #[serde(flatten)]
+    pub features: SpectralFeatures,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FeaturesFrame {
+    pub time: f64,
+    pub channels: Vec<ChannelFeatures>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum AnalyzerEvent {
+    Spectrum(SpectrumFrame),
+    SpectrogramRow(SpectrogramRow),
+    Waveform(WaveformFrame),
+    Pitch(PitchFrame),
+    Beat(BeatEvent),
+    Tempo(TempoFrame),
+    Loudness(LoudnessFrame),
+    Levels(LevelsFrame),
+    Stereo(StereoFrame),
+    Chroma(ChromaFrame),
+    Chord(ChordFrame),
+    Features(FeaturesFrame),
+}
+
+impl AnalyzerEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AnalyzerEvent::Spectrum(_) => "audio-data",
+            AnalyzerEvent::SpectrogramRow(_) => "spectrogram-row",
+            AnalyzerEvent::Waveform(_) => "waveform",
+            AnalyzerEvent::Pitch(_) => "pitch",
+            AnalyzerEvent::Beat(_) => "beat",
+            AnalyzerEvent::Tempo(_) => "tempo",
+            AnalyzerEvent::Loudness(_) => "loudness",
+            AnalyzerEvent::Levels(_) => "levels",
+            AnalyzerEvent::Stereo(_) => "stereo",
+            AnalyzerEvent::Chroma(_) => "chroma",
+            AnalyzerEvent::Chord(_) => "chord",
+            AnalyzerEvent::Features(_) => "features",
+        }
+    }
+}
+
+pub struct Analyzer {
+    config: AnalyzerConfig,
+    input_channels: Option<usize>,
+    labels: Vec<String>,
+    processors: Vec<AudioProcessor>,
+    windows: Vec<SlidingWindow>,
+    values: Vec<f32>,
+    agc: AutoGain,
+    spectrogram: Option<Spectrogram>,
+    onset: Option<OnsetDetector>,
+    tempo: Option<TempoTracker>,
+    loudness: Option<LoudnessMeter>,
+    levels: Option<LevelMeter>,
+    stereo: Option<StereoMeter>,
+    chroma: Option<ChromaTracker>,
+    chord: Option<ChordTracker>,
+    frames_seen: u64,
+}
+
+impl Analyzer {
+    pub fn new(config: AnalyzerConfig) -> Self {
+        Self {
+            input_channels: None,
+            labels: Vec::new(),
+            processors: Vec::new(),
+            windows: Vec::new(),
+            values: Vec::new(),
+            agc: AutoGain::new(config.agc.clone()),
+            spectrogram: None,
+            onset: None,
+            tempo: None,
+            loudness: None,
+            levels: None,
+            stereo: None,
+            chroma: None,
+            chord: None,
+            frames_seen: 0,
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &AnalyzerConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: AnalyzerConfig) -> Result<(), String> {
+        config.validate()?;
+        if let Some(channels) = self.input_channels {
+            match config.channel_mode {
+                ChannelMode::Single { channel } if channel >= channels => {
+                    return Err(format!(
+                        "channel {} is out of range for a {}-channel input",
+                        channel, channels
+                    ));
+                }
+                ChannelMode::Stereo { .. } if channels < 2 => {
+                    return Err(format!(
+                        "stereo mode requires a stereo input, got {} channel(s)",
+                        channels
+                    ));
+                }
+                _ => {}
+            }
+        }
+        self.config = config;
+        self.rebuild();
+        Ok(())
+    }
+
+    fn rebuild(&mut self) {
+        let channels = self.input_channels.unwrap_or(1);
+        self.labels = match self.config.channel_mode {
+            ChannelMode::Mixdown => vec!["mix".to_string()],
+            ChannelMode::Single { channel } => vec![format!("ch{}", channel.min(channels - 1))],
+            ChannelMode::Separate => (0..channels).map(|c| format!("ch{}", c)).collect(),
+            ChannelMode::Stereo { mid_side } => {
+                let mut labels = vec!["left".to_string(), "right".to_string()];
+                if mid_side {
+                    labels.extend(["mid".to_string(), "side".to_string()]);
+                }
+                labels
+            }
+        };
+        self.processors = self
+            .labels
+            .iter()
+            .map(|_| AudioProcessor::new(self.config.clone()))
+            .collect();
+        self.windows = self
+            .labels
+            .iter()
+            .map(|_| SlidingWindow::new(self.config.fft_size, self.config.hop_size()))
+            .collect();
+        self.values = vec![0.0; self.labels.len()];
+        self.agc = AutoGain::new(self.config.agc.clone());
+        self.spectrogram = self
+            .config
+            .spectrogram
+            .enabled
+            .then(|| Spectrogram::new(self.config.clone()));
+        // The tempo tracker runs on the onset envelope, so it needs the
+        // detector even when beat events are off.
+        self.onset = (self.config.onset.enabled || self.config.tempo.enabled)
+            .then(|| OnsetDetector::new(self.config.onset.clone()));
+        self.tempo = self
+            .config
+            .tempo
+            .enabled
+            .then(|| TempoTracker::new(self.config.tempo.clone()));
+        // Chord recognition runs on the chromagram, which is computed for it
+        // even when chroma events are off.
+        self.chroma = (self.config.chroma.enabled || self.config.chord.enabled)
+            .then(|| ChromaTracker::new(self.config.chroma.clone()));
+        self.chord = self
+            .config
+            .chord
+            .enabled
+            .then(|| ChordTracker::new(self.config.chord.clone()));
+        // Unrelated config changes must not restart a loudness measurement.
+        self.loudness = match self.loudness.take() {
+            Some(meter) if self.config.loudness.enabled => Some(meter),
+            _ => self.config.loudness.enabled.then(LoudnessMeter::new),
+        };
+        self.levels = self
+            .config
+            .levels
+            .enabled
+            .then(|| LevelMeter::new(self.config.levels.clone()));
+        self.stereo = self
+            .config
+            .stereo
+            .enabled
+            .then(|| StereoMeter::new(self.config.stereo.clone()));
+    }
+
+    pub fn spectrogram_snapshot(&self) -> Result<SpectrogramSnapshot, String> {
+        self.spectrogram
+            .as_ref()
+            .map(Spectrogram::snapshot)
+            .ok_or_else(|| "spectrogram is disabled".to_string())
+    }
+
+    pub fn reset_loudness(&mut self) -> Result<(), String> {
+        self.loudness
+            .as_mut()
+            .map(LoudnessMeter::reset)
+            .ok_or_else(|| "loudness metering is disabled".to_string())
+    }
+
+    // Maps one interleaved sample frame onto the streams selected by the
+    // channel mode.
+    fn split_frame(&mut self, frame: &[f32]) {
+        match self.config.channel_mode {
+            ChannelMode::Mixdown => {
+                self.values[0] = frame.iter().sum::<f32>() / frame.len() as f32;
+            }
+            ChannelMode::Single { channel } => {
+                self.values[0] = frame[channel.min(frame.len() - 1)];
+            }
+            ChannelMode::Separate => {
+                self.values.copy_from_slice(frame);
+            }
+            ChannelMode::Stereo { mid_side } => {
+                // Mono devices fall back to feeding the same signal to both sides.
+                let left = frame[0];
+                let right = frame[1.min(frame.len() - 1)];
+                self.values[0] = left;
+                self.values[1] = right;
+                if mid_side {
+                    self.values[2] = (left + right) * 0.5;
+                    self.values[3] = (left - right) * 0.5;
+                }
+            }
+        }
+    }
+
+    pub fn push(
+        &mut self,
+        data: &[f32],
+        channels: usize,
+        sample_rate: f32,
+        mut emit: impl FnMut(AnalyzerEvent),
+    ) {
+        let channels = channels.max(1);
+        if self.input_channels != Some(channels) || self.processors.is_empty() {
+            self.input_channels = Some(channels);
+            self.rebuild();
+        }
+
+        for frame in data.chunks_exact(channels) {
+            self.split_frame(frame);
+            self.frames_seen += 1;
+
+            // Meters read the raw device channels, independent of the channel mode.
+            let time = self.frames_seen as f64 / sample_rate as f64;
+            if let Some(meter) = &mut self.loudness {
+                if let Some(loudness) = meter.push(frame, sample_rate, time) {
+                    emit(AnalyzerEvent::Loudness(loudness));
+                }
+            }
+            if let Some(meter) = &mut self.levels {
+                if let Some(levels) = meter.push(frame, sample_rate, time) {
+                    emit(AnalyzerEvent::Levels(levels));
+                }
+            }
+            if let Some(meter) = &mut self.stereo {
+                if let Some(stereo) = meter.push(frame, sample_rate, time) {
+                    emit(AnalyzerEvent::Stereo(stereo));
+                }
+            }
+
+            let mut ready = false;
+            for (window, &value) in self.windows.iter_mut().zip(&self.values) {
+                ready = window.push(value);
+            }
+
+            if ready {
+                self.analyze(sample_rate, &mut emit);
+            }
+        }
+    }
+
+    fn analyze(&mut self, sample_rate: f32, emit: &mut impl FnMut(AnalyzerEvent)) {
+        let time = self.frames_seen as f64 / sample_rate as f64;
+        let samples: Vec<Vec<f32>> = self.windows.iter().map(SlidingWindow::frame).collect();
+        let mut frames: Vec<BarFrame> = self
+            .processors
+            .iter_mut()
+            .zip(&samples)
+            .map(|(proc, samples)| proc.process(samples, sample_rate))
+            .collect();
+
+        let freq_resolution = sample_rate / self.config.fft_size as f32;
+        let frame_ms = self.config.hop_size() as f32 / sample_rate * 1000.0;
+
+        // One gain for every stream, so their levels stay comparable.
+        let all_bars: Vec<f32> = frames.iter().flat_map(|f| f.bars.iter().copied()).collect();
+        let gain = self.agc.update(&all_bars, frame_ms);
+        for (proc, frame) in self.processors.iter_mut().zip(&mut frames) {
+            proc.finish(frame, gain, sample_rate);
+        }
+
+        // Single-stream features follow the first analyzed stream.
+        let magnitude = std::mem::take(&mut frames[0].magnitude);
+        let primary = &self.processors[0];
+
+        if self.config.scope.enabled {
+            let waveform = scope::waveform(&samples[0], &self.config.scope, time);
+            emit(AnalyzerEvent::Waveform(waveform));
+        }
+
+        if self.config.pitch.enabled {
+            let pitch = pitch::detect(&samples[0], sample_rate, &self.config.pitch, time);
+            emit(AnalyzerEvent::Pitch(pitch));
+        }
+
+        if let Some(onset) = &mut self.onset {
+            let (flux, beat) = onset.process(&magnitude, freq_resolution, frame_ms, time);
+            if let Some(beat) = beat.filter(|_| self.config.onset.enabled) {
+                emit(AnalyzerEvent::Beat(beat));
+            }
+            if let Some(tempo) = &mut self.tempo {
+                emit(AnalyzerEvent::Tempo(tempo.update(flux, frame_ms, time)));
+            }
+        }
+
+        if let Some(chroma) = &mut self.chroma {
+            let frame = chroma.process(&magnitude, freq_resolution, frame_ms, time);
+            let chord = self
+                .chord
+                .as_mut()
+                .map(|chord| chord.update(&frame.chroma, frame_ms, time));
+            if self.config.chroma.enabled {
+                emit(AnalyzerEvent::Chroma(frame));
+            }
+            if let Some(chord) = chord {
+                emit(AnalyzerEvent::Chord(chord));
+            }
+        }
+
+        if let Some(spectrogram) = &mut self.spectrogram {
+            let row = spectrogram.push(&magnitude, primary.window(), sample_rate, time);
+            emit(AnalyzerEvent::SpectrogramRow(row));
+        }
+
+        if self.config.features.enabled {
+            let channels = frames
+                .iter_mut()
+                .zip(&self.labels)
+                .filter_map(|(frame, label)| {
+                    frame.features.take().map(|features| ChannelFeatures {
+                        label: label.clone(),
+                        features,
+                    })
+                })
+                .collect();
+            emit(AnalyzerEvent::Features(FeaturesFrame { time, channels }));
+        }
+
+        let channels = frames
+            .into_iter()
+            .zip(&self.labels)
+            .map(|(frame, label)| ChannelSpectrum {
+                label: label.clone(),
+                bars: frame.bars,
+                peaks: frame.peaks,
+                gain: frame.gain,
+            })
+            .collect();
+
+        emit(AnalyzerEvent::Spectrum(SpectrumFrame {
+            bands: primary.bands().to_vec(),
+            channels,
+        }));
+    }
+}
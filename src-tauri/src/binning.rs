@@ -0,0 +1,319 @@
+use crate::config::BandAggregation;
+use crate::scale::Band;
+use crate::window::WindowShape;
+
+// Integral of the unit triangle centred on 0 from -inf to `t`.
+fn hat_integral(t: f32) -> f32 {
+    if t <= -1.0 {
+        0.0
+    } else if t <= 0.0 {
+        0.5 * (t + 1.0) * (t + 1.0)
+    } else if t <= 1.0 {
+        1.0 - 0.5 * (1.0 - t) * (1.0 - t)
+    } else {
+        1.0
+    }
+}
+
+// How much each FFT bin contributes to a band when the magnitude spectrum is
+// treated as linearly interpolated between bin centres. Bands narrower than a
+// bin still get their own value instead of repeating a neighbour's bin.
+pub struct BandWeights {
+    start: usize,
+    weights: Vec<f32>,
+    // Band edges in (fractional) bins.
+    low: f32,
+    high: f32,
+}
+
+impl BandWeights {
+    pub fn new(band: &Band, freq_resolution: f32, num_bins: usize) -> Self {
+        // DC is excluded; bands entirely outside the usable bins read the
+        // nearest edge bin.
+        let last = (num_bins - 1) as f32;
+        let low = (band.low / freq_resolution).clamp(1.0, last);
+        let high = (band.high / freq_resolution).clamp(1.0, last);
+        if high <= low {
+            return Self {
+                start: low as usize,
+                weights: vec![1.0],
+                low,
+                high: low,
+            };
+        }
+
+        let start = low.floor() as usize;
+        let end = (high.ceil() as usize).min(num_bins - 1);
+        let weights = (start..=end)
+            .map(|k| hat_integral(high - k as f32) - hat_integral(low - k as f32))
+            .collect();
+
+        Self {
+            start,
+            weights,
+            low,
+            high,
+        }
+    }
+
+    // Band width in bins; point bands read their single bin, so they weigh
+    // as one.
+    fn width(&self) -> f32 {
+        if self.high > self.low {
+            self.high - self.low
+        } else {
+            1.0
+        }
+    }
+
+    fn weighted_sum(&self, values: &[f32], f: impl Fn(f32) -> f32) -> f32 {
+        self.weights
+            .iter()
+            .zip(&values[self.start..])
+            .map(|(w, &v)| w * f(v))
+            .sum()
+    }
+
+    fn interpolate(values: &[f32], position: f32) -> f32 {
+        let k = position.floor() as usize;
+        let frac = position - k as f32;
+        let next = values[(k + 1).min(values.len() - 1)];
+        values[k] * (1.0 - frac) + next * frac
+    }
+
+    fn peak(&self, values: &[f32]) -> f32 {
+        let edges = Self::interpolate(values, self.low).max(Self::interpolate(values, self.high));
+        let interior_start = self.low.floor() as usize + 1;
+        let interior_end = self.high.ceil() as usize;
+        values
+            .get(interior_start..interior_end.min(values.len()))
+            .unwrap_or(&[])
+            .iter()
+            .fold(edges, |acc, &v| acc.max(v))
+    }
+
+    pub fn level(
+        &self,
+        magnitude: &[f32],
+        aggregation: BandAggregation,
+        window: &WindowShape,
+    ) -> f32 {
+        // The magnitudes are calibrated by coherent gain, so mean and peak are
+        // tone readings and shift with the window's main-lobe shape. The power
+        // aggregates divide out the noise bandwidth and read the same for any
+        // window.
+        match aggregation {
+            BandAggregation::Mean => self.weighted_sum(magnitude, |m| m) / self.width(),
+            BandAggregation::Peak => self.peak(magnitude),
+            // Band power averaged per bin, a noise-density reading.
+            BandAggregation::Rms => (self.weighted_sum(magnitude, |m| m * m)
+                / (self.width() * window.noise_bandwidth))
+                .sqrt(),
+            // Total band power; a tone's main lobe spreads over the window's
+            // noise bandwidth, so dividing by it keeps tones calibrated.
+            BandAggregation::PowerSum => {
+                (self.weighted_sum(magnitude, |m| m * m) / window.noise_bandwidth).sqrt()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustfft::{num_complex::Complex, FftPlanner};
+
+    use super::*;
+    use crate::window::WindowFunction;
+
+    fn band(low: f32, high: f32) -> Band {
+        Band {
+            low,
+            center: (low * high).sqrt(),
+            high,
+            nominal: None,
+        }
+    }
+
+    #[test]
+    fn flat_spectrum_reads_unity() {
+        // 1 Hz bins from a 128-point FFT; the rectangular window has unit noise
+        // bandwidth, so the power aggregates need no correction.
+        let magnitude = vec![1.0; 64];
+        let window = WindowFunction::Rectangular.build(128);
+        for (low, high) in [(10.2, 10.5), (10.0, 10.0), (10.5, 11.4), (10.3, 17.8)] {
+            let weights = BandWeights::new(&band(low, high), 1.0, magnitude.len());
+            for aggregation in [
+                BandAggregation::Mean,
+                BandAggregation::Peak,
+                BandAggregation::Rms,
+            ] {
+                let level = weights.level(&magnitude, aggregation, &window);
+                assert!(
+                    (level - 1.0).abs() < 1e-4,
+                    "{:?} over {}-{} read {}",
+                    aggregation,
+                    low,
+                    high,
+                    level
+                );
+            }
+            // A total rather than an average: reads 1 per bin of width, and a
+            // point band reads its single bin.
+            let expected = if high > low { (high - low).sqrt() } else { 1.0 };
+            let level = weights.level(&magnitude, BandAggregation::PowerSum, &window);
+            assert!(
+                (level - expected).abs() < 1e-4,
+                "PowerSum over {}-{} read {}",
+                low,
+                high,
+                level
+            );
+        }
+    }
+
+    const FFT_SIZE: usize = 1024;
+    const WINDOWS: [WindowFunction; 7] = [
+        WindowFunction::Hann,
+        WindowFunction::Hamming,
+        WindowFunction::Blackman,
+        WindowFunction::BlackmanHarris,
+        WindowFunction::FlatTop,
+        WindowFunction::Kaiser { beta: 8.6 },
+        WindowFunction::Rectangular,
+    ];
+
+    // Calibrated amplitude spectrum, as computed by the processor.
+    fn magnitude(samples: &[f32], window: &WindowShape) -> Vec<f32> {
+        let mut buffer: Vec<Complex<f32>> = samples
+            .iter()
+            .zip(&window.coefficients)
+            .map(|(&s, &w)| Complex::new(s * w, 0.0))
+            .collect();
+        FftPlanner::new()
+            .plan_fft_forward(FFT_SIZE)
+            .process(&mut buffer);
+        let scale = 2.0 / (FFT_SIZE as f32 * window.coherent_gain);
+        buffer[..FFT_SIZE / 2]
+            .iter()
+            .map(|c| c.norm() * scale)
+            .collect()
+    }
+
+    // Band level in dB at 1 Hz per bin, averaged in power over the frames.
+    fn band_db(
+        frames: &[Vec<f32>],
+        (low, high): (f32, f32),
+        function: WindowFunction,
+        aggregation: BandAggregation,
+    ) -> f32 {
+        let window = function.build(FFT_SIZE);
+        let weights = BandWeights::new(&band(low, high), 1.0, FFT_SIZE / 2);
+        let power = frames
+            .iter()
+            .map(|samples| {
+                let level = weights.level(&magnitude(samples, &window), aggregation, &window);
+                level * level
+            })
+            .sum::<f32>()
+            / frames.len() as f32;
+        10.0 * power.log10()
+    }
+
+    // Lowest and highest reading over every window function.
+    fn window_range_db(
+        frames: &[Vec<f32>],
+        range: (f32, f32),
+        aggregation: BandAggregation,
+    ) -> (f32, f32) {
+        WINDOWS
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(min, max), &function| {
+                let db = band_db(frames, range, function, aggregation);
+                (min.min(db), max.max(db))
+            })
+    }
+
+    #[test]
+    fn power_aggregates_are_window_independent() {
+        let tone = vec![(0..FFT_SIZE)
+            .map(|n| 0.5 * (std::f32::consts::TAU * 200.3 * n as f32 / FFT_SIZE as f32).sin())
+            .collect::<Vec<f32>>()];
+        // Uniform white noise from a fixed linear congruential generator.
+        let mut state = 1u32;
+        let noise: Vec<Vec<f32>> = (0..256)
+            .map(|_| {
+                (0..FFT_SIZE)
+                    .map(|_| {
+                        state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+                        (state >> 8) as f32 / (1u32 << 24) as f32 - 0.5
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // The tone needs a band wider than the flat-top's main lobe; noise is
+        // checked in a narrow band too, where the noise bandwidth matters most.
+        let cases = [
+            ("tone", &tone, (100.0, 300.0)),
+            ("noise", &noise, (100.0, 300.0)),
+            ("noise", &noise, (200.0, 204.0)),
+        ];
+        for aggregation in [BandAggregation::Rms, BandAggregation::PowerSum] {
+            for (name, frames, range) in cases {
+                let (min, max) = window_range_db(frames, range, aggregation);
+                assert!(
+                    max - min < 0.5,
+                    "{} {:?} over {:?} spans {} to {} dB",
+                    name,
+                    aggregation,
+                    range,
+                    min,
+                    max
+                );
+            }
+        }
+        // A tone's total band power is its amplitude.
+        let (min, max) = window_range_db(&tone, (100.0, 300.0), BandAggregation::PowerSum);
+        assert!(min > -6.3 && max < -5.8, "tone read {} to {} dB", min, max);
+    }
+
+    #[test]
+    fn tone_spectrum_reads_its_amplitude() {
+        let amplitude_db = 20.0 * 0.5f32.log10();
+        // On a bin centre every window reads the amplitude in the peak bin;
+        // between bins only the flat-top does.
+        let cases = [
+            (WindowFunction::Hann, 200.0),
+            (WindowFunction::FlatTop, 200.0),
+            (WindowFunction::FlatTop, 200.5),
+        ];
+        for (function, freq) in cases {
+            let window = function.build(FFT_SIZE);
+            let samples: Vec<f32> = (0..FFT_SIZE)
+                .map(|n| 0.5 * (std::f32::consts::TAU * freq * n as f32 / FFT_SIZE as f32).sin())
+                .collect();
+            let magnitude = magnitude(&samples, &window);
+            let read = |low: f32, high: f32, aggregation: BandAggregation| {
+                let weights = BandWeights::new(&band(low, high), 1.0, magnitude.len());
+                20.0 * weights.level(&magnitude, aggregation, &window).log10()
+            };
+
+            for (db, tolerance) in [
+                (read(freq - 0.1, freq + 0.1, BandAggregation::Peak), 0.1),
+                (read(freq - 0.1, freq + 0.1, BandAggregation::Mean), 0.3),
+                (
+                    read(freq - 50.0, freq + 50.0, BandAggregation::PowerSum),
+                    0.1,
+                ),
+            ] {
+                assert!(
+                    (db - amplitude_db).abs() < tolerance,
+                    "{:?} tone at {} Hz read {} dB",
+                    function,
+                    freq,
+                    db
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pitch::NOTE_NAMES;
+use crate::smoothing::smoothing_coefficient;
+
+// A different chord must beat the current one's smoothed score by this much
+// before the label changes, which keeps it from flickering between neighbours.
+const SWITCH_MARGIN: f32 = 0.03;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ChordConfig {
+    pub enabled: bool,
+    // Time constant of the per-chord template scores.
+    pub smoothing_ms: f32,
+    // Below this smoothed template similarity no chord is reported.
+    pub min_confidence: f32,
+}
+
+impl Default for ChordConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            smoothing_ms: 250.0,
+            min_confidence: 0.6,
+        }
+    }
+}
+
+impl ChordConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.smoothing_ms >= 0.0 && self.smoothing_ms <= 10_000.0) {
+            return Err(format!(
+                "chord smoothingMs must be between 0 and 10000, got {}",
+                self.smoothing_ms
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.min_confidence) {
+            return Err(format!(
+                "chord minConfidence must be between 0 and 1, got {}",
+                self.min_confidence
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChordQuality {
+    Major,
+    Minor,
+    Dominant7,
+    Major7,
+    Minor7,
+    Diminished,
+    Augmented,
+    Sus2,
+    Sus4,
+}
+
+impl ChordQuality {
+    const ALL: [ChordQuality; 9] = [
+        ChordQuality::Major,
+        ChordQuality::Minor,
+        ChordQuality::Dominant7,
+        ChordQuality::Major7,
+        ChordQuality::Minor7,
+        ChordQuality::Diminished,
+        ChordQuality::Augmented,
+        ChordQuality::Sus2,
+        ChordQuality::Sus4,
+    ];
+
+    // Semitones above the root.
+    fn intervals(&self) -> &'static [usize] {
+        match self {
+            ChordQuality::Major => &[0, 4, 7],
+            ChordQuality::Minor => &[0, 3, 7],
+            ChordQuality::Dominant7 => &[0, 4, 7, 10],
+            ChordQuality::Major7 => &[0, 4, 7, 11],
+            ChordQuality::Minor7 => &[0, 3, 7, 10],
+            ChordQuality::Diminished => &[0, 3, 6],
+            ChordQuality::Augmented => &[0, 4, 8],
+            ChordQuality::Sus2 => &[0, 2, 7],
+            ChordQuality::Sus4 => &[0, 5, 7],
+        }
+    }
+
+    fn suffix(&self) -> &'static str {
+        match self {
+            ChordQuality::Major => "",
+            ChordQuality::Minor => "m",
+            ChordQuality::Dominant7 => "7",
+            ChordQuality::Major7 => "maj7",
+            ChordQuality::Minor7 => "m7",
+            ChordQuality::Diminished => "dim",
+            ChordQuality::Augmented => "aug",
+            ChordQuality::Sus2 => "sus2",
+            ChordQuality::Sus4 => "sus4",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Chord {
+    // e.g. "Am", "G7", "Fmaj7".
+    pub label: String,
+    pub root: &'static str,
+    pub quality: ChordQuality,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChordFrame {
+    pub time: f64,
+    // None when nothing matches well enough.
+    pub chord: Option<Chord>,
+    pub confidence: f32,
+    // Whether the chord differs from the previous frame's.
+    pub changed: bool,
+    // Stream time at which the current chord (or silence) began.
+    pub since: f64,
+}
+
+struct Template {
+    root: usize,
+    quality: ChordQuality,
+    // Unit-length pitch-class vector.
+    weights: [f32; 12],
+}
+
+fn templates() -> Vec<Template> {
+    ChordQuality::ALL
+        .iter()
+        .flat_map(|&quality| {
+            (0..12).map(move |root| {
+                let intervals = quality.intervals();
+                let weight = 1.0 / (intervals.len() as f32).sqrt();
+                let mut weights = [0.0; 12];
+                for interval in intervals {
+                    weights[(root + interval) % 12] = weight;
+                }
+                Template {
+                    root,
+                    quality,
+                    weights,
+                }
+            })
+        })
+        .collect()
+}
+
+pub struct ChordTracker {
+    config: ChordConfig,
+    templates: Vec<Template>,
+    scores: Vec<f32>,
+    current: Option<usize>,
+    since: f64,
+}
+
+impl ChordTracker {
+    pub fn new(config: ChordConfig) -> Self {
+        let templates = templates();
+        Self {
+            scores: vec![0.0; templates.len()],
+            templates,
+            config,
+            current: None,
+            since: 0.0,
+        }
+    }
+
+    // Scores one chroma frame (C first) against every template by cosine
+    // similarity, smoothed over time.
+    pub fn update(&mut self, chroma: &[f32], frame_ms: f32, time: f64) -> ChordFrame {
+        let norm = chroma.iter().map(|v| v * v).sum::<f32>().sqrt();
+        let coeff = smoothing_coefficient(self.config.smoothing_ms, frame_ms);
+        for (score, template) in self.scores.iter_mut().zip(&self.templates) {
+            let similarity = if norm > 0.0 {
+                template
+                    .weights
+                    .iter()
+                    .zip(chroma)
+                    .map(|(w, v)| w * v)
+                    .sum::<f32>()
+                    / norm
+            } else {
+                0.0
+            };
+            *score += (similarity - *score) * coeff;
+        }
+
+        let (best, &best_score) = self
+            .scores
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+
+        let next = match self.current {
+            _ if best_score < self.config.min_confidence => None,
+            Some(current) if self.scores[current] + SWITCH_MARGIN >= best_score => Some(current),
+            _ => Some(best),
+        };
+        let changed = next != self.current;
+        if changed {
+            self.current = next;
+            self.since = time;
+        }
+
+        ChordFrame {
+            time,
+            chord: self.current.map(|index| {
+                let template = &self.templates[index];
+                let root = NOTE_NAMES[template.root];
+                Chord {
+                    label: format!("{}{}", root, template.quality.suffix()),
+                    root,
+                    quality: template.quality,
+                }
+            }),
+            confidence: self.current.map_or(0.0, |index| self.scores[index]),
+            changed,
+            since: self.since,
+        }
+    }
+}
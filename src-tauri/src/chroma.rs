@@ -0,0 +1,257 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pitch::NOTE_NAMES;
+use crate::smoothing::smoothing_coefficient;
+
+// Krumhansl-Kessler key profiles, tonic first.
+const MAJOR_PROFILE: [f32; 12] = [
+    6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88,
+];
+const MINOR_PROFILE: [f32; 12] = [
+    6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17,
+];
+// Frames with less spectral energy than this leave the rolling profile alone.
+const SILENCE_ENERGY: f32 = 1e-8;
+// Spectral peaks below these fractions of the frame maximum are ignored for
+// the chromagram and for tuning estimation respectively.
+const CHROMA_PEAK_RATIO: f32 = 0.01;
+const TUNING_PEAK_RATIO: f32 = 0.1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ChromaConfig {
+    pub enabled: bool,
+    // Spectral peaks outside this range are ignored; below a few hundred Hz
+    // neighbouring semitones only separate with a larger `fftSize`.
+    pub min_freq: f32,
+    pub max_freq: f32,
+    pub reference_a4: f32,
+    // Time constant of the rolling profile used for key and tuning estimation.
+    pub key_window_ms: f32,
+}
+
+impl Default for ChromaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_freq: 100.0,
+            max_freq: 5000.0,
+            reference_a4: 440.0,
+            key_window_ms: 10_000.0,
+        }
+    }
+}
+
+impl ChromaConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.min_freq > 0.0 && self.max_freq > self.min_freq) {
+            return Err(format!(
+                "chroma range must satisfy 0 < minFreq < maxFreq, got {} - {}",
+                self.min_freq, self.max_freq
+            ));
+        }
+        if !(400.0..=480.0).contains(&self.reference_a4) {
+            return Err(format!(
+                "chroma referenceA4 must be between 400 and 480 Hz, got {}",
+                self.reference_a4
+            ));
+        }
+        if !(self.key_window_ms > 0.0 && self.key_window_ms <= 120_000.0) {
+            return Err(format!(
+                "chroma keyWindowMs must be in (0, 120000], got {}",
+                self.key_window_ms
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Mode {
+    Major,
+    Minor,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Key {
+    pub tonic: &'static str,
+    pub mode: Mode,
+    // Camelot wheel code, e.g. "8B" for C major and "8A" for A minor.
+    pub camelot: String,
+    // Correlation of the rolling profile with the key's template.
+    pub confidence: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChromaFrame {
+    pub time: f64,
+    // Energy per pitch class starting at C, scaled so the strongest is 1.
+    pub chroma: Vec<f32>,
+    // Estimated deviation of the source's tuning from `reference_a4`.
+    pub tuning_cents: f32,
+    pub key: Option<Key>,
+}
+
+fn correlation(a: &[f32; 12], b: &[f32; 12]) -> f32 {
+    let mean_a = a.iter().sum::<f32>() / 12.0;
+    let mean_b = b.iter().sum::<f32>() / 12.0;
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        let (dx, dy) = (x - mean_a, y - mean_b);
+        cov += dx * dy;
+        var_a += dx * dx;
+        var_b += dy * dy;
+    }
+    let denom = (var_a * var_b).sqrt();
+    if denom > 0.0 {
+        cov / denom
+    } else {
+        0.0
+    }
+}
+
+fn camelot(tonic: usize, mode: Mode) -> String {
+    // Each step round the circle of fifths moves one position; a minor key
+    // shares the number of its relative major.
+    let major = match mode {
+        Mode::Major => tonic,
+        Mode::Minor => (tonic + 3) % 12,
+    };
+    let number = (major * 7 + 7) % 12 + 1;
+    let letter = match mode {
+        Mode::Major => 'B',
+        Mode::Minor => 'A',
+    };
+    format!("{}{}", number, letter)
+}
+
+// Krumhansl-Schmuckler key finding: the key whose rotated profile correlates
+// best with the observed pitch-class distribution.
+fn estimate_key(profile: &[f32; 12]) -> Option<Key> {
+    if profile.iter().all(|&v| v <= 0.0) {
+        return None;
+    }
+    let mut best: Option<(f32, usize, Mode)> = None;
+    for (template, mode) in [(&MAJOR_PROFILE, Mode::Major), (&MINOR_PROFILE, Mode::Minor)] {
+        for tonic in 0..12 {
+            let rotated: [f32; 12] = std::array::from_fn(|pc| template[(pc + 12 - tonic) % 12]);
+            let r = correlation(profile, &rotated);
+            if best.is_none_or(|(score, _, _)| r > score) {
+                best = Some((r, tonic, mode));
+            }
+        }
+    }
+    best.map(|(score, tonic, mode)| Key {
+        tonic: NOTE_NAMES[tonic],
+        mode,
+        camelot: camelot(tonic, mode),
+        confidence: score.clamp(0.0, 1.0),
+    })
+}
+
+pub struct ChromaTracker {
+    config: ChromaConfig,
+    // Running phasor of spectral-peak deviations from the equal-tempered grid;
+    // its angle is the tuning offset.
+    tuning: (f32, f32),
+    profile: [f32; 12],
+}
+
+impl ChromaTracker {
+    pub fn new(config: ChromaConfig) -> Self {
+        Self {
+            config,
+            tuning: (0.0, 0.0),
+            profile: [0.0; 12],
+        }
+    }
+
+    fn tuning_semitones(&self) -> f32 {
+        let (re, im) = self.tuning;
+        if re == 0.0 && im == 0.0 {
+            0.0
+        } else {
+            im.atan2(re) / std::f32::consts::TAU
+        }
+    }
+
+    pub fn process(
+        &mut self,
+        magnitude: &[f32],
+        freq_resolution: f32,
+        frame_ms: f32,
+        time: f64,
+    ) -> ChromaFrame {
+        let low = ((self.config.min_freq / freq_resolution).ceil() as usize).max(1);
+        let high = ((self.config.max_freq / freq_resolution).floor() as usize)
+            .min(magnitude.len().saturating_sub(2));
+        let coeff = smoothing_coefficient(self.config.key_window_ms, frame_ms);
+
+        // MIDI note number at a (fractional) bin, before tuning correction.
+        let semitone =
+            |bin: f32| 12.0 * (bin * freq_resolution / self.config.reference_a4).log2() + 69.0;
+
+        let max = magnitude[low.min(high)..=high]
+            .iter()
+            .fold(0.0f32, |acc, &m| acc.max(m));
+
+        // Spectral peaks as (semitone, magnitude). Only peaks count, so a low
+        // note whose main lobe spans several semitones still lands in one class.
+        let mut peaks = Vec::new();
+        for bin in low..=high {
+            let m = magnitude[bin];
+            if m >= max * CHROMA_PEAK_RATIO && m > magnitude[bin - 1] && m >= magnitude[bin + 1] {
+                // Parabolic interpolation on log magnitude locates the peak
+                // well inside the bin.
+                let (l, c, r) = (
+                    magnitude[bin - 1].max(1e-12).ln(),
+                    m.ln(),
+                    magnitude[bin + 1].max(1e-12).ln(),
+                );
+                let denom = l - 2.0 * c + r;
+                let offset = if denom < 0.0 {
+                    (0.5 * (l - r) / denom).clamp(-0.5, 0.5)
+                } else {
+                    0.0
+                };
+                peaks.push((semitone(bin as f32 + offset), m));
+            }
+        }
+
+        let (mut re, mut im) = (0.0, 0.0);
+        for &(s, m) in peaks.iter().filter(|p| p.1 >= max * TUNING_PEAK_RATIO) {
+            let angle = (s - s.round()) * std::f32::consts::TAU;
+            re += m * angle.cos();
+            im += m * angle.sin();
+        }
+        self.tuning.0 += (re - self.tuning.0) * coeff;
+        self.tuning.1 += (im - self.tuning.1) * coeff;
+        let tuning = self.tuning_semitones();
+
+        let mut chroma = [0.0f32; 12];
+        for &(s, m) in &peaks {
+            let pitch_class = (s - tuning).round() as i32;
+            chroma[pitch_class.rem_euclid(12) as usize] += m * m;
+        }
+
+        let energy: f32 = chroma.iter().sum();
+        if energy > SILENCE_ENERGY {
+            for (avg, value) in self.profile.iter_mut().zip(&chroma) {
+                *avg += (value / energy - *avg) * coeff;
+            }
+        }
+
+        let peak = chroma.iter().fold(0.0f32, |acc, &v| acc.max(v));
+        ChromaFrame {
+            time,
+            chroma: chroma
+                .iter()
+                .map(|&v| if peak > SILENCE_ENERGY { v / peak } else { 0.0 })
+                .collect(),
+            tuning_cents: tuning * 100.0,
+            key: estimate_key(&self.profile),
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use serde::{Deserialize, Serialize};
+
+use crate::agc::AgcConfig;
+use crate::chord::ChordConfig;
+use crate::chroma::ChromaConfig;
+use crate::features::FeaturesConfig;
+use crate::levels::LevelsConfig;
+use crate::loudness::LoudnessConfig;
+use crate::mapping::AmplitudeMapping;
+use crate::octave::{self, FRACTIONS};
+use crate::onset::OnsetConfig;
+use crate::peaks::PeakHoldConfig;
+use crate::pitch::PitchConfig;
+use crate::scale::{Band, FrequencyScale};
+use crate::scope::ScopeConfig;
+use crate::spectrogram::SpectrogramConfig;
+use crate::stereo::StereoConfig;
+use crate::tempo::TempoConfig;
+use crate::weighting::FrequencyWeighting;
+use crate::window::WindowFunction;
+
+const MIN_FFT_SIZE: usize = 256;
+const MAX_FFT_SIZE: usize = 32768;
+const MAX_BARS: usize = 512;
+const MAX_OVERLAP: f32 = 0.875;
+const MAX_TILT: f32 = 12.0;
+const MIN_FLOOR_DB: f32 = -160.0;
+const MAX_CEILING_DB: f32 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum ChannelMode {
+    #[default]
+    Mixdown,
+    Single {
+        channel: usize,
+    },
+    Separate,
+    Stereo {
+        #[serde(default)]
+        mid_side: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum BandMode {
+    #[default]
+    Scaled,
+    FractionalOctave {
+        fraction: u32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BandAggregation {
+    // Tone readings, calibrated by the window's coherent gain only.
+    Mean,
+    Peak,
+    // Power readings, also corrected for the window's noise bandwidth, so they
+    // stay comparable when the window changes.
+    Rms,
+    #[default]
+    PowerSum,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AnalyzerConfig {
+    pub fft_size: usize,
+    pub overlap: f32,
+    pub window: WindowFunction,
+    pub band_mode: BandMode,
+    pub num_bars: usize,
+    pub min_freq: f32,
+    pub max_freq: f32,
+    pub frequency_scale: FrequencyScale,
+    pub aggregation: BandAggregation,
+    pub weighting: FrequencyWeighting,
+    pub tilt_db_per_octave: f32,
+    pub floor_db: f32,
+    pub ceiling_db: f32,
+    pub amplitude_mapping: AmplitudeMapping,
+    pub attack_ms: f32,
+    pub release_ms: f32,
+    pub sensitivity: f32,
+    pub agc: AgcConfig,
+    pub peak_hold: PeakHoldConfig,
+    pub spectrogram: SpectrogramConfig,
+    pub scope: ScopeConfig,
+    pub pitch: PitchConfig,
+    pub onset: OnsetConfig,
+    pub tempo: TempoConfig,
+    pub loudness: LoudnessConfig,
+    pub levels: LevelsConfig,
+    pub stereo: StereoConfig,
+    pub chroma: ChromaConfig,
+    pub chord: ChordConfig,
+    pub features: FeaturesConfig,
+    pub channel_mode: ChannelMode,
+}
+
+impl Default for AnalyzerConfig {
+    fn default() -> Self {
+        Self {
+            fft_size: 2048,
+            overlap: 0.0,
+            window: WindowFunction::default(),
+            band_mode: BandMode::default(),
+            num_bars: 64,
+            min_freq: 20.0,
+            max_freq: 20000.0,
+            frequency_scale: FrequencyScale::default(),
+            aggregation: BandAggregation::default(),
+            weighting: FrequencyWeighting::default(),
+            tilt_db_per_octave: 3.0,
+            floor_db: -60.0,
+            ceiling_db: 0.0,
+            amplitude_mapping: AmplitudeMapping::default(),
+            attack_ms: 60.0,
+            release_ms: 260.0,
+            sensitivity: 1.5,
+            agc: AgcConfig::default(),
+            peak_hold: PeakHoldConfig::default(),
+            spectrogram: SpectrogramConfig::default(),
+            scope: ScopeConfig::default(),
+            pitch: PitchConfig::default(),
+            onset: OnsetConfig::default(),
+            tempo: TempoConfig::default(),
+            loudness: LoudnessConfig::default(),
+            levels: LevelsConfig::default(),
+            stereo: StereoConfig::default(),
+            chroma: ChromaConfig::default(),
+            chord: ChordConfig::default(),
+            features: FeaturesConfig::default(),
+            channel_mode: ChannelMode::default(),
+        }
+    }
+}
+
+impl AnalyzerConfig {
+    pub fn bands(&self) -> Vec<Band> {
+        match self.band_mode {
+            BandMode::Scaled => {
+                self.frequency_scale
+                    .bands(self.num_bars, self.min_freq, self.max_freq)
+            }
+            BandMode::FractionalOctave { fraction } => {
+                octave::fractional_octave_bands(fraction, self.min_freq, self.max_freq)
+            }
+        }
+    }
+
+    pub fn hop_size(&self) -> usize {
+        ((self.fft_size as f32 * (1.0 - self.overlap)).round() as usize).max(1)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.fft_size.is_power_of_two()
+            || !(MIN_FFT_SIZE..=MAX_FFT_SIZE).contains(&self.fft_size)
+        {
+            return Err(format!(
+                "fftSize must be a power of two between {} and {}, got {}",
+                MIN_FFT_SIZE, MAX_FFT_SIZE, self.fft_size
+            ));
+        }
+        if !(0.0..=MAX_OVERLAP).contains(&self.overlap) {
+            return Err(format!(
+                "overlap must be between 0 and {}, got {}",
+                MAX_OVERLAP, self.overlap
+            ));
+        }
+        self.window.validate()?;
+        if self.num_bars == 0 || self.num_bars > MAX_BARS {
+            return Err(format!(
+                "numBars must be between 1 and {}, got {}",
+                MAX_BARS, self.num_bars
+            ));
+        }
+        if !self.min_freq.is_finite() || self.min_freq <= 0.0 {
+            return Err(format!("minFreq must be positive, got {}", self.min_freq));
+        }
+        if !self.max_freq.is_finite() || self.max_freq <= self.min_freq {
+            return Err(format!(
+                "maxFreq must be greater than minFreq ({}), got {}",
+                self.min_freq, self.max_freq
+            ));
+        }
+        self.frequency_scale.validate()?;
+        if let BandMode::FractionalOctave { fraction } = self.band_mode {
+            if !FRACTIONS.contains(&fraction) {
+                return Err(format!(
+                    "fractional-octave fraction must be one of {:?}, got {}",
+                    FRACTIONS, fraction
+                ));
+            }
+            if self.bands().len() > MAX_BARS {
+                return Err(format!(
+                    "frequency range yields more than {} fractional-octave bands",
+                    MAX_BARS
+                ));
+            }
+        }
+        if !(-MAX_TILT..=MAX_TILT).contains(&self.tilt_db_per_octave) {
+            return Err(format!(
+                "tiltDbPerOctave must be between -{} and {}, got {}",
+                MAX_TILT, MAX_TILT, self.tilt_db_per_octave
+            ));
+        }
+        let range_ok = (MIN_FLOOR_DB..MAX_CEILING_DB).contains(&self.floor_db)
+            && self.ceiling_db > self.floor_db
+            && self.ceiling_db <= MAX_CEILING_DB;
+        if !range_ok {
+            return Err(format!(
+                "need {} <= floorDb < ceilingDb <= {} dBFS, got floorDb {} and ceilingDb {}",
+                MIN_FLOOR_DB, MAX_CEILING_DB, self.floor_db, self.ceiling_db
+            ));
+        }
+        self.amplitude_mapping.validate()?;
+        if !(self.attack_ms >= 0.0 && self.attack_ms.is_finite()) {
+            return Err(format!(
+                "attackMs must be non-negative, got {}",
+                self.attack_ms
+            ));
+        }
+        if !(self.release_ms >= 0.0 && self.release_ms.is_finite()) {
+            return Err(format!(
+                "releaseMs must be non-negative, got {}",
+                self.release_ms
+            ));
+        }
+        if !self.sensitivity.is_finite() || self.sensitivity <= 0.0 {
+            return Err(format!(
+                "sensitivity must be positive, got {}",
+                self.sensitivity
+            ));
+        }
+        self.agc.validate()?;
+        self.peak_hold.validate()?;
+        self.spectrogram.validate()?;
+        self.scope.validate()?;
+        self.pitch.validate()?;
+        self.onset.validate()?;
+        self.tempo.validate()?;
+        self.levels.validate()?;
+        self.stereo.validate()?;
+        self.chroma.validate()?;
+        self.chord.validate()?;
+        self.features.validate()
+    }
+}
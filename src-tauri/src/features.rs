@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+
+// Below this spectral energy descriptors are reported as zero rather than
+// computed from noise.
+const SILENCE_ENERGY: f32 = 1e-12;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FeaturesConfig {
+    pub enabled: bool,
+    // Fraction of spectral energy below the rolloff frequency.
+    pub rolloff: f32,
+}
+
+impl Default for FeaturesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rolloff: 0.85,
+        }
+    }
+}
+
+impl FeaturesConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.rolloff > 0.0 && self.rolloff < 1.0) {
+            return Err(format!(
+                "features rolloff must be in (0, 1), got {}",
+                self.rolloff
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpectralFeatures {
+    // Magnitude-weighted mean frequency in Hz ("brightness").
+    pub centroid: f32,
+    // Magnitude-weighted standard deviation around the centroid, in Hz.
+    pub spread: f32,
+    // Frequency in Hz below which the configured share of energy lies.
+    pub rolloff: f32,
+    // Geometric over arithmetic mean of the power spectrum: near 0 for tones,
+    // towards 1 for white noise ("noisiness").
+    pub flatness: f32,
+    // Distance between consecutive normalized spectra: 0 when static, 1 when
+    // no energy is shared.
+    pub flux: f32,
+    // Peak over mean magnitude.
+    pub crest: f32,
+    // Sign changes per sample of the time-domain window, 0..1.
+    pub zero_crossing_rate: f32,
+    // Time-domain RMS of the window, in full-scale units.
+    pub rms: f32,
+}
+
+pub struct FeatureExtractor {
+    config: FeaturesConfig,
+    // Previous spectrum scaled to unit length, for flux.
+    prev_normalized: Vec<f32>,
+}
+
+impl FeatureExtractor {
+    pub fn new(config: FeaturesConfig) -> Self {
+        Self {
+            config,
+            prev_normalized: Vec::new(),
+        }
+    }
+
+    // Descriptors of one analysis window. DC is excluded from the spectral ones.
+    pub fn extract(
+        &mut self,
+        samples: &[f32],
+        magnitude: &[f32],
+        freq_resolution: f32,
+    ) -> SpectralFeatures {
+        let crossings = samples
+            .windows(2)
+            .filter(|w| (w[0] >= 0.0) != (w[1] >= 0.0))
+            .count();
+        let mut features = SpectralFeatures {
+            zero_crossing_rate: crossings as f32 / samples.len().saturating_sub(1).max(1) as f32,
+            rms: (samples.iter().map(|s| s * s).sum::<f32>() / samples.len().max(1) as f32).sqrt(),
+            ..Default::default()
+        };
+
+        let spectrum = magnitude.get(1..).unwrap_or_default();
+        let freq = |i: usize| (i + 1) as f32 * freq_resolution;
+        let sum: f32 = spectrum.iter().sum();
+        let energy: f32 = spectrum.iter().map(|m| m * m).sum();
+
+        let norm = energy.sqrt();
+        let normalized: Vec<f32> = spectrum
+            .iter()
+            .map(|m| if norm > 0.0 { m / norm } else { 0.0 })
+            .collect();
+        if self.prev_normalized.len() == normalized.len() && energy > SILENCE_ENERGY {
+            let distance: f32 = normalized
+                .iter()
+                .zip(&self.prev_normalized)
+                .map(|(a, b)| (a - b) * (a - b))
+                .sum::<f32>()
+                .sqrt();
+            features.flux = distance / std::f32::consts::SQRT_2;
+        }
+        self.prev_normalized = normalized;
+
+        if energy <= SILENCE_ENERGY {
+            return features;
+        }
+
+        let centroid = spectrum
+            .iter()
+            .enumerate()
+            .map(|(i, m)| freq(i) * m)
+            .sum::<f32>()
+            / sum;
+        let variance = spectrum
+            .iter()
+            .enumerate()
+            .map(|(i, m)| (freq(i) - centroid).powi(2) * m)
+            .sum::<f32>()
+            / sum;
+
+        let threshold = energy * self.config.rolloff;
+        let mut cumulative = 0.0;
+        let rolloff_bin = spectrum
+            .iter()
+            .position(|m| {
+                cumulative += m * m;
+                cumulative >= threshold
+            })
+            .unwrap_or(spectrum.len() - 1);
+
+        let mean_log_power = spectrum
+            .iter()
+            .map(|m| (m * m).max(1e-20).ln())
+            .sum::<f32>()
+            / spectrum.len() as f32;
+        let mean_power = energy / spectrum.len() as f32;
+        let peak = spectrum.iter().fold(0.0f32, |acc, &m| acc.max(m));
+
+        features.centroid = centroid;
+        features.spread = variance.sqrt();
+        features.rolloff = freq(rolloff_bin);
+        features.flatness = (mean_log_power.exp() / mean_power).clamp(0.0, 1.0);
+        features.crest = peak / (sum / spectrum.len() as f32);
+        features
+    }
+}
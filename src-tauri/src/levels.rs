@@ -0,0 +1,211 @@
+use serde::{Deserialize, Serialize};
+
+use crate::smoothing::smoothing_coefficient;
+
+// Readings are clamped here so silence stays a finite number.
+const MIN_DB: f32 = -120.0;
+
+// VU ballistics: a critically damped second-order response reaching 99 % of
+// a step in 300 ms (IEC 60268-17), scaled so a sine reads its RMS.
+const VU_TIME_CONSTANT_MS: f32 = 300.0 / 6.64;
+const VU_SINE_SCALE: f32 = std::f32::consts::PI / (2.0 * std::f32::consts::SQRT_2);
+
+// Quasi-peak ballistics (IEC 60268-10). The attack time constants give -1 dB
+// (type I) and -4 dB (type II) on a 10 ms tone burst relative to a steady
+// tone, and the gains bring the steady-tone reading up to the tone's peak.
+const PPM1_ATTACK_MS: f32 = 1.7;
+const PPM1_GAIN_DB: f32 = 0.23;
+const PPM1_FALL_DB_PER_SEC: f32 = 20.0 / 1.5;
+const PPM2_ATTACK_MS: f32 = 5.2;
+const PPM2_GAIN_DB: f32 = 0.36;
+const PPM2_FALL_DB_PER_SEC: f32 = 24.0 / 2.8;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LevelsConfig {
+    pub enabled: bool,
+    // How often a `levels` event is emitted.
+    pub interval_ms: f32,
+    // Level that reads 0 VU; -18 dBFS follows EBU R68.
+    pub vu_reference_dbfs: f32,
+    // Averaging time of the RMS reading.
+    pub rms_ms: f32,
+}
+
+impl Default for LevelsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_ms: 50.0,
+            vu_reference_dbfs: -18.0,
+            rms_ms: 300.0,
+        }
+    }
+}
+
+impl LevelsConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(10.0..=1000.0).contains(&self.interval_ms) {
+            return Err(format!(
+                "levels intervalMs must be between 10 and 1000, got {}",
+                self.interval_ms
+            ));
+        }
+        if !(-40.0..=0.0).contains(&self.vu_reference_dbfs) {
+            return Err(format!(
+                "levels vuReferenceDbfs must be between -40 and 0, got {}",
+                self.vu_reference_dbfs
+            ));
+        }
+        if !(self.rms_ms > 0.0 && self.rms_ms <= 10_000.0) {
+            return Err(format!(
+                "levels rmsMs must be in (0, 10000], got {}",
+                self.rms_ms
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelLevels {
+    pub label: String,
+    // Highest absolute sample since the previous event, in dBFS.
+    pub peak: f32,
+    pub rms: f32,
+    // Relative to `vu_reference_dbfs`.
+    pub vu: f32,
+    pub ppm_type1: f32,
+    pub ppm_type2: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LevelsFrame {
+    pub time: f64,
+    pub channels: Vec<ChannelLevels>,
+}
+
+fn to_db(level: f32) -> f32 {
+    (20.0 * level.max(1e-10).log10()).max(MIN_DB)
+}
+
+#[derive(Default)]
+struct ChannelState {
+    vu: [f32; 2],
+    ppm1: f32,
+    ppm2: f32,
+    mean_square: f32,
+    peak: f32,
+}
+
+// Per-sample smoothing coefficients and fall factors for one sample rate.
+struct Ballistics {
+    vu: f32,
+    rms: f32,
+    ppm1_attack: f32,
+    ppm1_fall: f32,
+    ppm2_attack: f32,
+    ppm2_fall: f32,
+}
+
+impl Ballistics {
+    fn new(config: &LevelsConfig, sample_rate: f32) -> Self {
+        let sample_ms = 1000.0 / sample_rate;
+        let coefficient = |time_ms: f32| smoothing_coefficient(time_ms, sample_ms);
+        let fall = |db_per_sec: f32| 10f32.powf(-db_per_sec / (20.0 * sample_rate));
+        Self {
+            vu: coefficient(VU_TIME_CONSTANT_MS),
+            rms: coefficient(config.rms_ms),
+            ppm1_attack: coefficient(PPM1_ATTACK_MS),
+            ppm1_fall: fall(PPM1_FALL_DB_PER_SEC),
+            ppm2_attack: coefficient(PPM2_ATTACK_MS),
+            ppm2_fall: fall(PPM2_FALL_DB_PER_SEC),
+        }
+    }
+}
+
+// Rectifier charging a capacitor through the attack time constant, which
+// discharges at the meter's fall rate.
+fn quasi_peak(level: f32, input: f32, attack: f32, fall: f32) -> f32 {
+    let fallen = level * fall;
+    if input > fallen {
+        fallen + (input - fallen) * attack
+    } else {
+        fallen
+    }
+}
+
+pub struct LevelMeter {
+    config: LevelsConfig,
+    sample_rate: f32,
+    ballistics: Ballistics,
+    channels: Vec<ChannelState>,
+    interval: usize,
+    pos: usize,
+}
+
+impl LevelMeter {
+    pub fn new(config: LevelsConfig) -> Self {
+        Self {
+            ballistics: Ballistics::new(&config, 48000.0),
+            config,
+            sample_rate: 0.0,
+            channels: Vec::new(),
+            interval: 0,
+            pos: 0,
+        }
+    }
+
+    fn configure(&mut self, channels: usize, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.ballistics = Ballistics::new(&self.config, sample_rate);
+        self.channels = (0..channels).map(|_| ChannelState::default()).collect();
+        self.interval = ((self.config.interval_ms / 1000.0 * sample_rate).round() as usize).max(1);
+        self.pos = 0;
+    }
+
+    // Feeds one interleaved sample frame; returns readings every `interval_ms`.
+    pub fn push(&mut self, frame: &[f32], sample_rate: f32, time: f64) -> Option<LevelsFrame> {
+        if frame.len() != self.channels.len() || sample_rate != self.sample_rate {
+            self.configure(frame.len(), sample_rate);
+        }
+
+        let b = &self.ballistics;
+        for (channel, &x) in self.channels.iter_mut().zip(frame) {
+            let rectified = x.abs();
+            channel.peak = channel.peak.max(rectified);
+            channel.mean_square += (x * x - channel.mean_square) * b.rms;
+            channel.vu[0] += (rectified - channel.vu[0]) * b.vu;
+            channel.vu[1] += (channel.vu[0] - channel.vu[1]) * b.vu;
+            channel.ppm1 = quasi_peak(channel.ppm1, rectified, b.ppm1_attack, b.ppm1_fall);
+            channel.ppm2 = quasi_peak(channel.ppm2, rectified, b.ppm2_attack, b.ppm2_fall);
+        }
+
+        self.pos += 1;
+        if self.pos < self.interval {
+            return None;
+        }
+        self.pos = 0;
+
+        let reference = self.config.vu_reference_dbfs;
+        let channels = self
+            .channels
+            .iter_mut()
+            .enumerate()
+            .map(|(c, channel)| {
+                let peak = std::mem::take(&mut channel.peak);
+                ChannelLevels {
+                    label: format!("ch{}", c),
+                    peak: to_db(peak),
+                    rms: to_db(channel.mean_square.sqrt()),
+                    vu: to_db(channel.vu[1] * VU_SINE_SCALE) - reference,
+                    ppm_type1: to_db(channel.ppm1 * 10f32.powf(PPM1_GAIN_DB / 20.0)),
+                    ppm_type2: to_db(channel.ppm2 * 10f32.powf(PPM2_GAIN_DB / 20.0)),
+                }
+            })
+            .collect();
+
+        Some(LevelsFrame { time, channels })
+    }
+}
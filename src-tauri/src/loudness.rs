@@ -0,0 +1,314 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::biquad::Biquad;
+use crate::window::WindowFunction;
+
+// Measurements advance in 100 ms steps; momentary and short-term loudness
+// span 4 and 30 of them (BS.1770-4, EBU Tech 3341).
+const STEP_SECONDS: f32 = 0.1;
+const MOMENTARY_STEPS: usize = 4;
+const SHORT_TERM_STEPS: usize = 30;
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+// Relative gates, as energy ratios: -10 LU for integrated loudness and
+// -20 LU for loudness range (EBU Tech 3342).
+const INTEGRATED_GATE: f64 = 0.1;
+const RANGE_GATE: f64 = 0.01;
+
+// True-peak interpolator: 4x oversampling with 12 taps per phase.
+const OVERSAMPLING: usize = 4;
+const TAPS_PER_PHASE: usize = 12;
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LoudnessConfig {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoudnessFrame {
+    pub time: f64,
+    // LUFS; None until enough audio has been measured or below the gate.
+    pub momentary: Option<f32>,
+    pub short_term: Option<f32>,
+    pub integrated: Option<f32>,
+    // LU.
+    pub loudness_range: Option<f32>,
+    // Maximum true peak since the last reset, in dBTP.
+    pub true_peak: Option<f32>,
+}
+
+fn to_lufs(energy: f64) -> f64 {
+    -0.691 + 10.0 * energy.log10()
+}
+
+fn to_energy(lufs: f64) -> f64 {
+    10f64.powf((lufs + 0.691) / 10.0)
+}
+
+fn gated_lufs(energy: f64) -> Option<f32> {
+    let lufs = to_lufs(energy);
+    (lufs >= ABSOLUTE_GATE_LUFS).then_some(lufs as f32)
+}
+
+// Windowed-sinc interpolation filter split into polyphase branches. Phase 0
+// reproduces the input samples; the others interpolate between them.
+fn true_peak_phases() -> Vec<[f32; TAPS_PER_PHASE]> {
+    let len = OVERSAMPLING * TAPS_PER_PHASE;
+    let center = len / 2;
+    let window = WindowFunction::Kaiser { beta: 6.0 }.build(len + 1);
+
+    (0..OVERSAMPLING)
+        .map(|phase| {
+            let mut taps = [0.0; TAPS_PER_PHASE];
+            for (i, tap) in taps.iter_mut().enumerate() {
+                let n = phase + OVERSAMPLING * i;
+                let t = (n as f32 - center as f32) / OVERSAMPLING as f32;
+                let sinc = if t == 0.0 {
+                    1.0
+                } else {
+                    (std::f32::consts::PI * t).sin() / (std::f32::consts::PI * t)
+                };
+                *tap = sinc * window.coefficients[n];
+            }
+            let sum: f32 = taps.iter().sum();
+            taps.map(|tap| tap / sum)
+        })
+        .collect()
+}
+
+// BS.1770 channel weights. Six-channel input is taken as 5.1 in the usual
+// L R C LFE Ls Rs order; anything else weights every channel equally.
+fn channel_weight(channel: usize, channels: usize) -> f64 {
+    match (channels, channel) {
+        (6, 3) => 0.0,
+        (6, 4) | (6, 5) => 1.41,
+        _ => 1.0,
+    }
+}
+
+struct ChannelState {
+    shelf: Biquad,
+    highpass: Biquad,
+    weight: f64,
+    sum_squares: f64,
+    // Most recent input samples, newest first, for the true-peak filter.
+    history: [f32; TAPS_PER_PHASE],
+}
+
+pub struct LoudnessMeter {
+    sample_rate: f32,
+    channels: Vec<ChannelState>,
+    phases: Vec<[f32; TAPS_PER_PHASE]>,
+    step_len: usize,
+    step_pos: usize,
+    // Weighted mean-square energy of the latest steps, oldest first.
+    steps: VecDeque<f64>,
+    // Momentary block energies above the absolute gate, for integration.
+    blocks: Vec<f64>,
+    // Short-term energies above the absolute gate, for the loudness range.
+    short_terms: Vec<f64>,
+    true_peak: f32,
+}
+
+impl LoudnessMeter {
+    pub fn new() -> Self {
+        Self {
+            sample_rate: 0.0,
+            channels: Vec::new(),
+            phases: true_peak_phases(),
+            step_len: 0,
+            step_pos: 0,
+            steps: VecDeque::with_capacity(SHORT_TERM_STEPS),
+            blocks: Vec::new(),
+            short_terms: Vec::new(),
+            true_peak: 0.0,
+        }
+    }
+
+    fn configure(&mut self, channels: usize, sample_rate: f32) {
+        let fs = sample_rate as f64;
+        self.sample_rate = sample_rate;
+        self.channels = (0..channels)
+            .map(|c| ChannelState {
+                shelf: Biquad::k_shelf(fs),
+                highpass: Biquad::k_highpass(fs),
+                weight: channel_weight(c, channels),
+                sum_squares: 0.0,
+                history: [0.0; TAPS_PER_PHASE],
+            })
+            .collect();
+        self.step_len = ((sample_rate * STEP_SECONDS).round() as usize).max(1);
+        self.reset();
+    }
+
+    // Starts a new measurement, keeping the filters settled.
+    pub fn reset(&mut self) {
+        for channel in &mut self.channels {
+            channel.sum_squares = 0.0;
+        }
+        self.step_pos = 0;
+        self.steps.clear();
+        self.blocks.clear();
+        self.short_terms.clear();
+        self.true_peak = 0.0;
+    }
+
+    // Feeds one interleaved sample frame; returns a measurement every 100 ms.
+    pub fn push(&mut self, frame: &[f32], sample_rate: f32, time: f64) -> Option<LoudnessFrame> {
+        if frame.len() != self.channels.len() || sample_rate != self.sample_rate {
+            self.configure(frame.len(), sample_rate);
+        }
+
+        for (channel, &x) in self.channels.iter_mut().zip(frame) {
+            let y = channel.highpass.process(channel.shelf.process(x as f64));
+            channel.sum_squares += y * y;
+
+            channel.history.copy_within(0..TAPS_PER_PHASE - 1, 1);
+            channel.history[0] = x;
+            for taps in &self.phases {
+                let y: f32 = taps.iter().zip(&channel.history).map(|(h, x)| h * x).sum();
+                self.true_peak = self.true_peak.max(y.abs());
+            }
+        }
+
+        self.step_pos += 1;
+        if self.step_pos < self.step_len {
+            return None;
+        }
+        self.step_pos = 0;
+
+        let energy = self
+            .channels
+            .iter_mut()
+            .map(|channel| {
+                let mean_square = channel.sum_squares / self.step_len as f64;
+                channel.sum_squares = 0.0;
+                channel.weight * mean_square
+            })
+            .sum();
+        if self.steps.len() == SHORT_TERM_STEPS {
+            self.steps.pop_front();
+        }
+        self.steps.push_back(energy);
+
+        Some(self.measure(time))
+    }
+
+    fn measure(&mut self, time: f64) -> LoudnessFrame {
+        let mean_of_last = |n: usize| -> Option<f64> {
+            (self.steps.len() >= n).then(|| self.steps.iter().rev().take(n).sum::<f64>() / n as f64)
+        };
+        let momentary = mean_of_last(MOMENTARY_STEPS);
+        let short_term = mean_of_last(SHORT_TERM_STEPS);
+
+        let absolute_gate = to_energy(ABSOLUTE_GATE_LUFS);
+        if let Some(energy) = momentary.filter(|&e| e >= absolute_gate) {
+            self.blocks.push(energy);
+        }
+        if let Some(energy) = short_term.filter(|&e| e >= absolute_gate) {
+            self.short_terms.push(energy);
+        }
+
+        LoudnessFrame {
+            time,
+            momentary: momentary.and_then(gated_lufs),
+            short_term: short_term.and_then(gated_lufs),
+            integrated: self.integrated(),
+            loudness_range: self.loudness_range(),
+            true_peak: (self.true_peak > 0.0).then(|| 20.0 * self.true_peak.log10()),
+        }
+    }
+
+    fn integrated(&self) -> Option<f32> {
+        if self.blocks.is_empty() {
+            return None;
+        }
+        let mean = self.blocks.iter().sum::<f64>() / self.blocks.len() as f64;
+        let gate = mean * INTEGRATED_GATE;
+        let (sum, count) = self
+            .blocks
+            .iter()
+            .filter(|&&e| e > gate)
+            .fold((0.0, 0usize), |(sum, count), e| (sum + e, count + 1));
+        (count > 0).then(|| to_lufs(sum / count as f64) as f32)
+    }
+
+    fn loudness_range(&self) -> Option<f32> {
+        if self.short_terms.is_empty() {
+            return None;
+        }
+        let mean = self.short_terms.iter().sum::<f64>() / self.short_terms.len() as f64;
+        let gate = mean * RANGE_GATE;
+        let mut gated: Vec<f64> = self
+            .short_terms
+            .iter()
+            .copied()
+            .filter(|&e| e > gate)
+            .collect();
+        if gated.is_empty() {
+            return None;
+        }
+        gated.sort_by(f64::total_cmp);
+        let percentile = |p: f64| gated[((gated.len() - 1) as f64 * p).round() as usize];
+        Some((to_lufs(percentile(0.95)) - to_lufs(percentile(0.10))) as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 48000.0;
+
+    // Feeds `seconds` of a stereo sine to the meter and returns the last
+    // measurement.
+    fn measure_sine(
+        meter: &mut LoudnessMeter,
+        freq: f32,
+        amplitude: f32,
+        phase: f32,
+        seconds: f32,
+    ) -> LoudnessFrame {
+        let mut last = None;
+        for n in 0..(seconds * SAMPLE_RATE) as usize {
+            let x =
+                amplitude * (std::f32::consts::TAU * freq * n as f32 / SAMPLE_RATE + phase).sin();
+            let time = n as f64 / SAMPLE_RATE as f64;
+            last = meter.push(&[x, x], SAMPLE_RATE, time).or(last);
+        }
+        last.unwrap()
+    }
+
+    #[test]
+    fn reference_tone_reads_its_level() {
+        // EBU Tech 3341 case 1: a stereo 1 kHz sine at -23 dBFS reads -23 LUFS.
+        let mut meter = LoudnessMeter::new();
+        let frame = measure_sine(&mut meter, 1000.0, 10f32.powf(-23.0 / 20.0), 0.0, 20.0);
+        for reading in [frame.momentary, frame.short_term, frame.integrated] {
+            let lufs = reading.unwrap();
+            assert!((lufs + 23.0).abs() < 0.1, "read {} LUFS", lufs);
+        }
+    }
+
+    #[test]
+    fn true_peak_finds_intersample_peak() {
+        // EBU Tech 3341 case 15: a 0.5 FS sine at fs/4 with 45 degree phase has
+        // samples at -9 dBFS but a true peak of -6 dBTP (+0.2 / -0.4 dB).
+        let mut meter = LoudnessMeter::new();
+        let frame = measure_sine(
+            &mut meter,
+            SAMPLE_RATE / 4.0,
+            0.5,
+            std::f32::consts::FRAC_PI_4,
+            1.0,
+        );
+        let true_peak = frame.true_peak.unwrap();
+        assert!(
+            (-6.42..=-5.82).contains(&true_peak),
+            "read {} dBTP",
+            true_peak
+        );
+    }
+}
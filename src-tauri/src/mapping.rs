@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AmplitudeMapping {
+    #[default]
+    LinearDb,
+    LinearAmplitude,
+    Sqrt,
+    Power {
+        gamma: f32,
+    },
+}
+
+impl AmplitudeMapping {
+    pub fn validate(&self) -> Result<(), String> {
+        if let AmplitudeMapping::Power { gamma } = *self {
+            if !(gamma > 0.0 && gamma <= 10.0) {
+                return Err(format!("power gamma must be in (0, 10], got {}", gamma));
+            }
+        }
+        Ok(())
+    }
+
+    // Maps a level in dBFS onto 0..1 between `floor_db` and `ceiling_db`.
+    pub fn map(&self, db: f32, floor_db: f32, ceiling_db: f32) -> f32 {
+        let linear_amplitude = || {
+            let amp = 10f32.powf(db / 20.0);
+            let floor = 10f32.powf(floor_db / 20.0);
+            let ceiling = 10f32.powf(ceiling_db / 20.0);
+            ((amp - floor) / (ceiling - floor)).clamp(0.0, 1.0)
+        };
+
+        match *self {
+            AmplitudeMapping::LinearDb => {
+                ((db - floor_db) / (ceiling_db - floor_db)).clamp(0.0, 1.0)
+            }
+            AmplitudeMapping::LinearAmplitude => linear_amplitude(),
+            AmplitudeMapping::Sqrt => linear_amplitude().sqrt(),
+            AmplitudeMapping::Power { gamma } => linear_amplitude().powf(gamma),
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate::scale::Band;
+
+pub const FRACTIONS: [u32; 4] = [1, 3, 6, 12];
+
+// IEC 61260-1 base-ten octave ratio.
+const OCTAVE_RATIO: f64 = 1.995_262_314_968_879_5; // 10^(3/10)
+const REFERENCE_FREQ: f64 = 1000.0;
+
+// ISO 266 preferred-number mantissas.
+const R10: [f64; 10] = [1.0, 1.25, 1.6, 2.0, 2.5, 3.15, 4.0, 5.0, 6.3, 8.0];
+const R40: [f64; 40] = [
+    1.0, 1.06, 1.12, 1.18, 1.25, 1.32, 1.4, 1.5, 1.6, 1.7, 1.8, 1.9, 2.0, 2.12, 2.24, 2.36, 2.5,
+    2.65, 2.8, 3.0, 3.15, 3.35, 3.55, 3.75, 4.0, 4.25, 4.5, 4.75, 5.0, 5.3, 5.6, 6.0, 6.3, 6.7,
+    7.1, 7.5, 8.0, 8.5, 9.0, 9.5,
+];
+
+fn nearest_preferred(freq: f64, series: &[f64]) -> f64 {
+    let decade = 10f64.powf(freq.log10().floor());
+    let mantissa = freq / decade;
+    let best = series
+        .iter()
+        .chain(std::iter::once(&10.0))
+        .min_by(|a, b| {
+            let da = (mantissa.ln() - a.ln()).abs();
+            let db = (mantissa.ln() - b.ln()).abs();
+            da.total_cmp(&db)
+        })
+        .copied()
+        .unwrap_or(1.0);
+    best * decade
+}
+
+fn nominal_frequency(exact: f64, fraction: u32) -> f64 {
+    match fraction {
+        1 | 3 => nearest_preferred(exact, &R10),
+        6 => nearest_preferred(exact, &R40),
+        _ => {
+            // No preferred series is defined this fine; use three significant figures.
+            let scale = 10f64.powf(exact.log10().floor() - 2.0);
+            (exact / scale).round() * scale
+        }
+    }
+}
+
+// Generates the 1/`fraction`-octave bands that overlap `min..max`.
+pub fn fractional_octave_bands(fraction: u32, min: f32, max: f32) -> Vec<Band> {
+    let b = fraction as f64;
+    let (min, max) = (min as f64, max as f64);
+
+    // Odd fractions centre a band on 1 kHz; even fractions straddle it.
+    let center = |x: i32| {
+        let exponent = if fraction % 2 == 1 {
+            x as f64 / b
+        } else {
+            (2 * x + 1) as f64 / (2.0 * b)
+        };
+        REFERENCE_FREQ * OCTAVE_RATIO.powf(exponent)
+    };
+    let half_band = OCTAVE_RATIO.powf(1.0 / (2.0 * b));
+
+    let first = ((min / REFERENCE_FREQ).log(OCTAVE_RATIO) * b).floor() as i32 - 1;
+    let last = ((max / REFERENCE_FREQ).log(OCTAVE_RATIO) * b).ceil() as i32 + 1;
+
+    (first..=last)
+        .map(center)
+        .filter(|&fm| fm * half_band > min && fm / half_band < max)
+        .map(|fm| Band {
+            low: (fm / half_band) as f32,
+            center: fm as f32,
+            high: (fm * half_band) as f32,
+            nominal: Some(nominal_frequency(fm, fraction) as f32),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nominals(fraction: u32, min: f32, max: f32) -> Vec<f32> {
+        fractional_octave_bands(fraction, min, max)
+            .iter()
+            .map(|band| band.nominal.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn nominal_centres_follow_iso_266() {
+        assert_eq!(
+            nominals(1, 25.0, 16000.0),
+            [31.5, 63.0, 125.0, 250.0, 500.0, 1000.0, 2000.0, 4000.0, 8000.0, 16000.0]
+        );
+        assert_eq!(
+            nominals(3, 20.0, 20000.0),
+            [
+                20.0, 25.0, 31.5, 40.0, 50.0, 63.0, 80.0, 100.0, 125.0, 160.0, 200.0, 250.0, 315.0,
+                400.0, 500.0, 630.0, 800.0, 1000.0, 1250.0, 1600.0, 2000.0, 2500.0, 3150.0, 4000.0,
+                5000.0, 6300.0, 8000.0, 10000.0, 12500.0, 16000.0, 20000.0,
+            ]
+        );
+        // Even fractions straddle 1 kHz.
+        assert_eq!(nominals(6, 900.0, 1100.0), [950.0, 1060.0]);
+        assert_eq!(nominals(12, 900.0, 1100.0), [917.0, 972.0, 1030.0, 1090.0]);
+    }
+
+    #[test]
+    fn bands_follow_iec_61260() {
+        let third = fractional_octave_bands(3, 900.0, 1100.0);
+        assert_eq!(third.len(), 1);
+        assert!((third[0].center - 1000.0).abs() < 1e-3);
+        assert!((third[0].low - 891.251).abs() < 1e-2);
+        assert!((third[0].high - 1122.018).abs() < 1e-2);
+
+        for fraction in FRACTIONS {
+            let bands = fractional_octave_bands(fraction, 20.0, 20000.0);
+            let ratio = 10f32.powf(0.3 / fraction as f32);
+            for band in &bands {
+                assert!((band.high / band.low / ratio - 1.0).abs() < 1e-5);
+                assert!((band.center / (band.low * band.high).sqrt() - 1.0).abs() < 1e-5);
+            }
+            for pair in bands.windows(2) {
+                assert!((pair[0].high / pair[1].low - 1.0).abs() < 1e-5);
+            }
+        }
+    }
+}
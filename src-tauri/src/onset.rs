@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+// Log compression applied to magnitudes before differencing.
+const COMPRESSION: f32 = 100.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OnsetConfig {
+    pub enabled: bool,
+    // Frequency range the flux is computed over; narrow it to e.g. 30-150 Hz
+    // to react to kicks only.
+    pub min_freq: f32,
+    pub max_freq: f32,
+    // Flux must exceed the running median by this factor to count as an onset.
+    pub threshold: f32,
+    // Length of the running median window.
+    pub window_ms: f32,
+    pub min_interval_ms: f32,
+}
+
+impl Default for OnsetConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_freq: 20.0,
+            max_freq: 20000.0,
+            threshold: 1.5,
+            window_ms: 1000.0,
+            min_interval_ms: 100.0,
+        }
+    }
+}
+
+impl OnsetConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.min_freq >= 0.0 && self.max_freq > self.min_freq) {
+            return Err(format!(
+                "onset range must satisfy 0 <= minFreq < maxFreq, got {} - {}",
+                self.min_freq, self.max_freq
+            ));
+        }
+        if !(self.threshold >= 1.0 && self.threshold.is_finite()) {
+            return Err(format!(
+                "onset threshold must be at least 1, got {}",
+                self.threshold
+            ));
+        }
+        if !(self.window_ms > 0.0 && self.min_interval_ms >= 0.0) {
+            return Err(
+                "onset windowMs must be positive and minIntervalMs non-negative".to_string(),
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BeatEvent {
+    pub time: f64,
+    // 0..1, how far the flux peak rose above the adaptive threshold.
+    pub strength: f32,
+}
+
+pub struct OnsetDetector {
+    config: OnsetConfig,
+    prev_log_magnitude: Vec<f32>,
+    history: VecDeque<f32>,
+    // The previous two flux values and the time of the newer one, for
+    // picking local maxima one frame late.
+    prev_flux: [f32; 2],
+    prev_time: f64,
+    last_onset: f64,
+}
+
+impl OnsetDetector {
+    pub fn new(config: OnsetConfig) -> Self {
+        Self {
+            config,
+            prev_log_magnitude: Vec::new(),
+            history: VecDeque::new(),
+            prev_flux: [0.0; 2],
+            prev_time: 0.0,
+            last_onset: f64::NEG_INFINITY,
+        }
+    }
+
+    // Half-wave rectified spectral flux of log-compressed magnitudes.
+    fn flux(&mut self, magnitude: &[f32], freq_resolution: f32) -> f32 {
+        let low = (self.config.min_freq / freq_resolution).floor() as usize;
+        let high = ((self.config.max_freq / freq_resolution).ceil() as usize).min(magnitude.len());
+        let low = low.max(1).min(high);
+
+        let log_magnitude: Vec<f32> = magnitude[low..high]
+            .iter()
+            .map(|m| (1.0 + COMPRESSION * m).ln())
+            .collect();
+        let flux = if log_magnitude.len() == self.prev_log_magnitude.len() {
+            log_magnitude
+                .iter()
+                .zip(&self.prev_log_magnitude)
+                .map(|(cur, prev)| (cur - prev).max(0.0))
+                .sum::<f32>()
+                / log_magnitude.len().max(1) as f32
+        } else {
+            0.0
+        };
+        self.prev_log_magnitude = log_magnitude;
+        flux
+    }
+
+    // Feeds one frame and returns its onset-strength value (the flux) and any
+    // beat detected at the previous frame.
+    pub fn process(
+        &mut self,
+        magnitude: &[f32],
+        freq_resolution: f32,
+        frame_ms: f32,
+        time: f64,
+    ) -> (f32, Option<BeatEvent>) {
+        let flux = self.flux(magnitude, freq_resolution);
+
+        let max_history = ((self.config.window_ms / frame_ms).ceil() as usize).max(3);
+        while self.history.len() >= max_history {
+            self.history.pop_front();
+        }
+        self.history.push_back(flux);
+
+        let mut sorted: Vec<f32> = self.history.iter().copied().collect();
+        sorted.sort_by(f32::total_cmp);
+        let median = sorted[sorted.len() / 2];
+        let threshold = median * self.config.threshold + 1e-4;
+
+        let [before, candidate] = self.prev_flux;
+        let is_peak = candidate > before && candidate >= flux && candidate > threshold;
+        let spaced =
+            (self.prev_time - self.last_onset) * 1000.0 >= self.config.min_interval_ms as f64;
+
+        let beat = (is_peak && spaced).then(|| {
+            self.last_onset = self.prev_time;
+            BeatEvent {
+                time: self.prev_time,
+                strength: (1.0 - threshold / candidate).clamp(0.0, 1.0),
+            }
+        });
+
+        self.prev_flux = [candidate, flux];
+        self.prev_time = time;
+        (flux, beat)
+    }
+}
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PeakHoldConfig {
+    pub hold_ms: f32,
+    pub fall_db_per_sec: f32,
+}
+
+impl Default for PeakHoldConfig {
+    fn default() -> Self {
+        Self {
+            hold_ms: 800.0,
+            fall_db_per_sec: 24.0,
+        }
+    }
+}
+
+impl PeakHoldConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.hold_ms >= 0.0 && self.hold_ms.is_finite()) {
+            return Err(format!(
+                "peak holdMs must be non-negative, got {}",
+                self.hold_ms
+            ));
+        }
+        if !(self.fall_db_per_sec > 0.0 && self.fall_db_per_sec.is_finite()) {
+            return Err(format!(
+                "peak fallDbPerSec must be positive, got {}",
+                self.fall_db_per_sec
+            ));
+        }
+        Ok(())
+    }
+}
+
+// Per-band peak levels in dB that hold for `hold_ms` and then fall linearly.
+pub struct PeakHold {
+    config: PeakHoldConfig,
+    peaks_db: Vec<f32>,
+    held_ms: Vec<f32>,
+}
+
+impl PeakHold {
+    pub fn new(config: PeakHoldConfig, num_bands: usize) -> Self {
+        Self {
+            config,
+            peaks_db: vec![f32::NEG_INFINITY; num_bands],
+            held_ms: vec![0.0; num_bands],
+        }
+    }
+
+    pub fn peaks_db(&self) -> &[f32] {
+        &self.peaks_db
+    }
+
+    pub fn update(&mut self, levels_db: &[f32], frame_ms: f32) {
+        let fall = self.config.fall_db_per_sec * frame_ms / 1000.0;
+        for ((peak, held), &level) in self
+            .peaks_db
+            .iter_mut()
+            .zip(&mut self.held_ms)
+            .zip(levels_db)
+        {
+            if level >= *peak {
+                *peak = level;
+                *held = 0.0;
+            } else if *held < self.config.hold_ms {
+                *held += frame_ms;
+            } else {
+                *peak = (*peak - fall).max(level);
+            }
+        }
+    }
+}
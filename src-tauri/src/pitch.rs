@@ -0,0 +1,210 @@
+use serde::{Deserialize, Serialize};
+
+pub const NOTE_NAMES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+// Below this RMS the input is treated as silence.
+const SILENCE_RMS: f32 = 1e-4;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PitchConfig {
+    pub enabled: bool,
+    pub min_freq: f32,
+    pub max_freq: f32,
+    // YIN absolute threshold on the normalized difference function.
+    pub threshold: f32,
+    pub reference_a4: f32,
+}
+
+impl Default for PitchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_freq: 60.0,
+            max_freq: 1500.0,
+            threshold: 0.15,
+            reference_a4: 440.0,
+        }
+    }
+}
+
+impl PitchConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.min_freq > 0.0 && self.max_freq > self.min_freq) {
+            return Err(format!(
+                "pitch range must satisfy 0 < minFreq < maxFreq, got {} - {}",
+                self.min_freq, self.max_freq
+            ));
+        }
+        if !(self.threshold > 0.0 && self.threshold < 1.0) {
+            return Err(format!(
+                "pitch threshold must be in (0, 1), got {}",
+                self.threshold
+            ));
+        }
+        if !(400.0..=480.0).contains(&self.reference_a4) {
+            return Err(format!(
+                "pitch referenceA4 must be between 400 and 480 Hz, got {}",
+                self.reference_a4
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Note {
+    pub name: &'static str,
+    pub octave: i32,
+    pub midi: i32,
+    // Deviation from the nearest equal-tempered note.
+    pub cents: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PitchFrame {
+    pub time: f64,
+    pub frequency: Option<f32>,
+    pub confidence: f32,
+    pub note: Option<Note>,
+}
+
+pub fn nearest_note(frequency: f32, reference_a4: f32) -> Note {
+    let semitones = 12.0 * (frequency / reference_a4).log2() + 69.0;
+    let midi = semitones.round() as i32;
+    Note {
+        name: NOTE_NAMES[midi.rem_euclid(12) as usize],
+        octave: midi.div_euclid(12) - 1,
+        midi,
+        cents: (semitones - midi as f32) * 100.0,
+    }
+}
+
+// YIN fundamental frequency estimator (de Cheveigné & Kawahara, 2002).
+pub fn detect(samples: &[f32], sample_rate: f32, config: &PitchConfig, time: f64) -> PitchFrame {
+    let unpitched = PitchFrame {
+        time,
+        frequency: None,
+        confidence: 0.0,
+        note: None,
+    };
+
+    let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+    if rms < SILENCE_RMS {
+        return unpitched;
+    }
+
+    let tau_min = ((sample_rate / config.max_freq).floor() as usize).max(2);
+    let tau_max = ((sample_rate / config.min_freq).ceil() as usize).min(samples.len() / 2);
+    if tau_max <= tau_min + 1 {
+        return unpitched;
+    }
+    let width = samples.len() - tau_max;
+
+    // Cumulative mean normalized difference function.
+    let mut cmnd = vec![1.0f32; tau_max + 1];
+    let mut running_sum = 0.0f32;
+    for (tau, value) in cmnd.iter_mut().enumerate().skip(1) {
+        let diff: f32 = samples[..width]
+            .iter()
+            .zip(&samples[tau..tau + width])
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum();
+        running_sum += diff;
+        *value = if running_sum > 0.0 {
+            diff * tau as f32 / running_sum
+        } else {
+            1.0
+        };
+    }
+
+    // First dip below the threshold, followed down to its local minimum.
+    let Some(mut tau) = (tau_min..tau_max).find(|&t| cmnd[t] < config.threshold) else {
+        return unpitched;
+    };
+    while tau + 1 < tau_max && cmnd[tau + 1] < cmnd[tau] {
+        tau += 1;
+    }
+
+    // Parabolic interpolation around the minimum for sub-sample accuracy.
+    let (prev, cur, next) = (cmnd[tau - 1], cmnd[tau], cmnd[tau + 1]);
+    let denom = prev - 2.0 * cur + next;
+    let offset = if denom.abs() > f32::EPSILON {
+        (0.5 * (prev - next) / denom).clamp(-1.0, 1.0)
+    } else {
+        0.0
+    };
+
+    let frequency = sample_rate / (tau as f32 + offset);
+    PitchFrame {
+        time,
+        frequency: Some(frequency),
+        confidence: (1.0 - cur).clamp(0.0, 1.0),
+        note: Some(nearest_note(frequency, config.reference_a4)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 48000.0;
+
+    fn sine(freq: f32) -> Vec<f32> {
+        (0..2048)
+            .map(|n| 0.5 * (std::f32::consts::TAU * freq * n as f32 / SAMPLE_RATE).sin())
+            .collect()
+    }
+
+    #[test]
+    fn sines_read_their_pitch() {
+        let config = PitchConfig::default();
+        for (freq, name, octave) in [(82.41, "E", 2), (440.0, "A", 4), (1318.51, "E", 6)] {
+            let frame = detect(&sine(freq), SAMPLE_RATE, &config, 0.0);
+            let frequency = frame.frequency.unwrap();
+            let note = frame.note.unwrap();
+            assert!(
+                (1200.0 * (frequency / freq).log2()).abs() < 1.0,
+                "{} Hz read {} Hz",
+                freq,
+                frequency
+            );
+            assert_eq!((note.name, note.octave), (name, octave));
+            assert!(
+                note.cents.abs() < 1.0,
+                "{} Hz read {} cents",
+                freq,
+                note.cents
+            );
+            assert!(frame.confidence > 0.9);
+        }
+    }
+
+    #[test]
+    fn silence_has_no_pitch() {
+        let frame = detect(&[0.0; 2048], SAMPLE_RATE, &PitchConfig::default(), 0.0);
+        assert!(frame.frequency.is_none() && frame.note.is_none());
+    }
+
+    #[test]
+    fn notes_follow_equal_temperament() {
+        let note = nearest_note(261.63, 440.0);
+        assert_eq!((note.name, note.octave, note.midi), ("C", 4, 60));
+        let note = nearest_note(445.0, 440.0);
+        assert_eq!((note.name, note.octave, note.midi), ("A", 4, 69));
+        assert!(
+            (note.cents - 19.56).abs() < 0.01,
+            "read {} cents",
+            note.cents
+        );
+        // Against a 432 Hz reference, 440 Hz sits 31.77 cents sharp of A4.
+        let note = nearest_note(440.0, 432.0);
+        assert_eq!(note.midi, 69);
+        assert!(
+            (note.cents - 31.77).abs() < 0.01,
+            "read {} cents",
+            note.cents
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+use std::sync::Arc;
+
+use crate::binning::BandWeights;
+use crate::config::AnalyzerConfig;
+use crate::features::{FeatureExtractor, SpectralFeatures};
+use crate::peaks::PeakHold;
+use crate::scale::Band;
+use crate::smoothing::smoothing_coefficient;
+use crate::weighting;
+use crate::window::WindowShape;
+
+pub struct BarFrame {
+    // Ungained bar targets from `process`, smoothed bars after `finish`.
+    pub bars: Vec<f32>,
+    pub peaks: Vec<f32>,
+    pub gain: f32,
+    levels_db: Vec<f32>,
+    // Calibrated amplitude spectrum up to Nyquist, for downstream analysis.
+    pub magnitude: Vec<f32>,
+    pub features: Option<SpectralFeatures>,
+}
+
+pub struct AudioProcessor {
+    config: AnalyzerConfig,
+    peak_hold: PeakHold,
+    prev_bars: Vec<f32>,
+    bands: Vec<Band>,
+    band_weights: Vec<BandWeights>,
+    weights_resolution: f32,
+    // Per-band weighting curve plus tilt, evaluated at the band centre.
+    band_gains_db: Vec<f32>,
+    window: WindowShape,
+    fft: Arc<dyn Fft<f32>>,
+    features: Option<FeatureExtractor>,
+}
+
+impl AudioProcessor {
+    pub fn new(config: AnalyzerConfig) -> Self {
+        let fft = FftPlanner::<f32>::new().plan_fft_forward(config.fft_size);
+        let bands = config.bands();
+        let band_gains_db = bands
+            .iter()
+            .map(|band| {
+                config.weighting.gain_db(band.center)
+                    + weighting::tilt_db(band.center, config.tilt_db_per_octave)
+            })
+            .collect();
+
+        let band_count = bands.len();
+
+        Self {
+            prev_bars: vec![0.0; band_count],
+            bands,
+            band_weights: Vec::new(),
+            weights_resolution: 0.0,
+            band_gains_db,
+            peak_hold: PeakHold::new(config.peak_hold.clone(), band_count),
+            window: config.window.build(config.fft_size),
+            fft,
+            features: config
+                .features
+                .enabled
+                .then(|| FeatureExtractor::new(config.features.clone())),
+            config,
+        }
+    }
+
+    fn to_bar(&self, db: f32) -> f32 {
+        let config = &self.config;
+        config
+            .amplitude_mapping
+            .map(db, config.floor_db, config.ceiling_db)
+            * config.sensitivity
+    }
+
+    pub fn window(&self) -> &WindowShape {
+        &self.window
+    }
+
+    pub fn bands(&self) -> &[Band] {
+        &self.bands
+    }
+
+    pub fn process(&mut self, samples: &[f32], sample_rate: f32) -> BarFrame {
+        let fft_size = self.config.fft_size;
+
+        let mut buffer: Vec<Complex<f32>> = samples
+            .iter()
+            .zip(&self.window.coefficients)
+            .map(|(&s, &w)| Complex::new(s * w, 0.0))
+            .collect();
+
+        self.fft.process(&mut buffer);
+
+        let freq_resolution = sample_rate / fft_size as f32;
+        let amplitude_scale = 2.0 / (fft_size as f32 * self.window.coherent_gain);
+        let magnitude: Vec<f32> = buffer
+            .iter()
+            .take(fft_size / 2)
+            .map(|c| c.norm() * amplitude_scale)
+            .collect();
+
+        let features = self
+            .features
+            .as_mut()
+            .map(|extractor| extractor.extract(samples, &magnitude, freq_resolution));
+
+        if freq_resolution != self.weights_resolution {
+            self.band_weights = self
+                .bands
+                .iter()
+                .map(|band| BandWeights::new(band, freq_resolution, magnitude.len()))
+                .collect();
+            self.weights_resolution = freq_resolution;
+        }
+
+        let levels_db: Vec<f32> = self
+            .band_weights
+            .iter()
+            .zip(&self.band_gains_db)
+            .map(|(weights, gain_db)| {
+                let level = weights.level(&magnitude, self.config.aggregation, &self.window);
+                20.0 * (level.max(1e-10)).log10() + gain_db
+            })
+            .collect();
+
+        BarFrame {
+            bars: levels_db.iter().map(|&db| self.to_bar(db)).collect(),
+            peaks: Vec::new(),
+            gain: 1.0,
+            levels_db,
+            magnitude,
+            features,
+        }
+    }
+
+    // Applies the gain shared by all streams, then bar ballistics and peak hold.
+    pub fn finish(&mut self, frame: &mut BarFrame, gain: f32, sample_rate: f32) {
+        let frame_ms = self.config.hop_size() as f32 / sample_rate * 1000.0;
+        let rise = smoothing_coefficient(self.config.attack_ms, frame_ms);
+        let fall = smoothing_coefficient(self.config.release_ms, frame_ms);
+        for (prev, &bar) in self.prev_bars.iter_mut().zip(&frame.bars) {
+            let target = (bar * gain).min(1.5);
+            let coeff = if target > *prev { rise } else { fall };
+            *prev += (target - *prev) * coeff;
+        }
+
+        self.peak_hold.update(&frame.levels_db, frame_ms);
+        frame.peaks = self
+            .peak_hold
+            .peaks_db()
+            .iter()
+            .zip(&self.prev_bars)
+            .map(|(&db, &bar)| (self.to_bar(db) * gain).min(1.5).max(bar))
+            .collect();
+        frame.bars.clone_from(&self.prev_bars);
+        frame.gain = gain;
+    }
+}
@@ -0,0 +1,42 @@
+pub struct SlidingWindow {
+    data: Vec<f32>,
+    pos: usize,
+    filled: usize,
+    hop: usize,
+    since_frame: usize,
+}
+
+impl SlidingWindow {
+    pub fn new(size: usize, hop: usize) -> Self {
+        Self {
+            data: vec![0.0; size],
+            pos: 0,
+            filled: 0,
+            hop: hop.clamp(1, size),
+            since_frame: 0,
+        }
+    }
+
+    // Returns true once a full window is available and `hop` new samples have
+    // arrived since the previous frame.
+    pub fn push(&mut self, sample: f32) -> bool {
+        self.data[self.pos] = sample;
+        self.pos = (self.pos + 1) % self.data.len();
+        self.filled = (self.filled + 1).min(self.data.len());
+        self.since_frame += 1;
+
+        if self.filled == self.data.len() && self.since_frame >= self.hop {
+            self.since_frame = 0;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn frame(&self) -> Vec<f32> {
+        let mut out = Vec::with_capacity(self.data.len());
+        out.extend_from_slice(&self.data[self.pos..]);
+        out.extend_from_slice(&self.data[..self.pos]);
+        out
+    }
+}
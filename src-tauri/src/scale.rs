@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Band {
+    pub low: f32,
+    pub center: f32,
+    pub high: f32,
+    // Preferred-number label for standardized bands, e.g. 31.5 Hz.
+    pub nominal: Option<f32>,
+}
+
+impl Band {
+    pub fn from_edges(low: f32, high: f32) -> Self {
+        Self {
+            low,
+            center: (low * high).sqrt(),
+            high,
+            nominal: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum FrequencyScale {
+    Linear,
+    #[default]
+    Log,
+    Mel,
+    Bark,
+    Erb,
+    Hybrid {
+        linear_weight: f32,
+    },
+}
+
+impl FrequencyScale {
+    pub fn validate(&self) -> Result<(), String> {
+        if let FrequencyScale::Hybrid { linear_weight } = *self {
+            if !(0.0..=1.0).contains(&linear_weight) {
+                return Err(format!(
+                    "hybrid linearWeight must be between 0 and 1, got {}",
+                    linear_weight
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn warp(&self, freq: f32) -> f32 {
+        match *self {
+            FrequencyScale::Linear => freq,
+            FrequencyScale::Log => freq.max(1e-3).ln(),
+            FrequencyScale::Mel => 2595.0 * (1.0 + freq / 700.0).log10(),
+            // Traunmüller's approximation of the critical-band rate.
+            FrequencyScale::Bark => 26.81 * freq / (1960.0 + freq) - 0.53,
+            FrequencyScale::Erb => 21.4 * (1.0 + 0.00437 * freq).log10(),
+            FrequencyScale::Hybrid { .. } => unreachable!("hybrid scale is not a single warp"),
+        }
+    }
+
+    fn unwarp(&self, value: f32) -> f32 {
+        match *self {
+            FrequencyScale::Linear => value,
+            FrequencyScale::Log => value.exp(),
+            FrequencyScale::Mel => 700.0 * (10f32.powf(value / 2595.0) - 1.0),
+            FrequencyScale::Bark => 1960.0 * (value + 0.53) / (26.28 - value),
+            FrequencyScale::Erb => (10f32.powf(value / 21.4) - 1.0) / 0.00437,
+            FrequencyScale::Hybrid { .. } => unreachable!("hybrid scale is not a single warp"),
+        }
+    }
+
+    // Normalized position (0..1) of `freq` between `min` and `max` on this scale.
+    pub fn position(&self, freq: f32, min: f32, max: f32) -> f32 {
+        match *self {
+            FrequencyScale::Hybrid { linear_weight } => {
+                let lin = FrequencyScale::Linear.position(freq, min, max);
+                let log = FrequencyScale::Log.position(freq, min, max);
+                linear_weight * lin + (1.0 - linear_weight) * log
+            }
+            _ => {
+                let lo = self.warp(min);
+                let hi = self.warp(max);
+                (self.warp(freq) - lo) / (hi - lo)
+            }
+        }
+    }
+
+    // Inverse of `position`.
+    pub fn frequency_at(&self, position: f32, min: f32, max: f32) -> f32 {
+        match *self {
+            FrequencyScale::Hybrid { .. } => {
+                // The blend has no closed-form inverse but is monotonic, so bisect.
+                let (mut lo, mut hi) = (min, max);
+                for _ in 0..40 {
+                    let mid = 0.5 * (lo + hi);
+                    if self.position(mid, min, max) < position {
+                        lo = mid;
+                    } else {
+                        hi = mid;
+                    }
+                }
+                0.5 * (lo + hi)
+            }
+            _ => {
+                let lo = self.warp(min);
+                let hi = self.warp(max);
+                self.unwarp(lo + (hi - lo) * position)
+            }
+        }
+    }
+
+    pub fn bands(&self, num_bands: usize, min: f32, max: f32) -> Vec<Band> {
+        let edges: Vec<f32> = (0..=num_bands)
+            .map(|i| self.frequency_at(i as f32 / num_bands as f32, min, max))
+            .collect();
+        edges
+            .windows(2)
+            .map(|w| Band::from_edges(w[0], w[1]))
+            .collect()
+    }
+}
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+const MAX_COLUMNS: usize = 4096;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ScopeConfig {
+    pub enabled: bool,
+    // Pixel columns the waveform is decimated to.
+    pub columns: usize,
+    // Align the trace to a rising crossing of `trigger_level`.
+    pub trigger: bool,
+    pub trigger_level: f32,
+}
+
+impl Default for ScopeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            columns: 512,
+            trigger: true,
+            trigger_level: 0.0,
+        }
+    }
+}
+
+impl ScopeConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.columns == 0 || self.columns > MAX_COLUMNS {
+            return Err(format!(
+                "scope columns must be between 1 and {}, got {}",
+                MAX_COLUMNS, self.columns
+            ));
+        }
+        if !(-1.0..=1.0).contains(&self.trigger_level) {
+            return Err(format!(
+                "scope triggerLevel must be between -1 and 1, got {}",
+                self.trigger_level
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WaveformFrame {
+    pub time: f64,
+    // Whether the trace starts on a trigger crossing; free-running otherwise.
+    pub triggered: bool,
+    // (min, max) sample values per column.
+    pub columns: Vec<(f32, f32)>,
+}
+
+// Decimates half of the analysis window into min/max columns. Using half the
+// window leaves the other half as search room for the trigger, so the trace
+// length stays constant whether or not a crossing is found.
+pub fn waveform(samples: &[f32], config: &ScopeConfig, time: f64) -> WaveformFrame {
+    let span = samples.len() / 2;
+    let level = config.trigger_level;
+
+    let trigger = config
+        .trigger
+        .then(|| (1..=span).find(|&i| samples[i - 1] < level && samples[i] >= level))
+        .flatten();
+    let start = trigger.unwrap_or(samples.len() - span);
+    let trace = &samples[start..start + span];
+
+    let num_columns = config.columns.min(span).max(1);
+    let columns = (0..num_columns)
+        .map(|c| {
+            let from = c * span / num_columns;
+            let to = ((c + 1) * span / num_columns).max(from + 1);
+            trace[from..to]
+                .iter()
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &s| {
+                    (lo.min(s), hi.max(s))
+                })
+        })
+        .collect();
+
+    WaveformFrame {
+        time,
+        triggered: trigger.is_some(),
+        columns,
+    }
+}
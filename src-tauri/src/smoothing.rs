@@ -0,0 +1,10 @@
+// One-pole smoothing coefficient for a time constant, given the time between
+// updates (frames or samples), so ballistics don't depend on sample rate, FFT
+// size or overlap.
+pub(crate) fn smoothing_coefficient(time_ms: f32, step_ms: f32) -> f32 {
+    if time_ms <= 0.0 {
+        1.0
+    } else {
+        1.0 - (-step_ms / time_ms).exp()
+    }
+}
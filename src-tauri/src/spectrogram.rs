@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use crate::binning::BandWeights;
+use crate::config::AnalyzerConfig;
+use crate::scale::Band;
+use crate::window::WindowShape;
+
+const MAX_HISTORY: usize = 4096;
+const MAX_BINS: usize = 2048;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SpectrogramConfig {
+    pub enabled: bool,
+    // Number of frames kept for snapshots.
+    pub history: usize,
+    // Frequency bins per row, spread over the analyzer's range and scale.
+    pub bins: usize,
+}
+
+impl Default for SpectrogramConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            history: 256,
+            bins: 256,
+        }
+    }
+}
+
+impl SpectrogramConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.history == 0 || self.history > MAX_HISTORY {
+            return Err(format!(
+                "spectrogram history must be between 1 and {}, got {}",
+                MAX_HISTORY, self.history
+            ));
+        }
+        if self.bins == 0 || self.bins > MAX_BINS {
+            return Err(format!(
+                "spectrogram bins must be between 1 and {}, got {}",
+                MAX_BINS, self.bins
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpectrogramRow {
+    // Monotonic frame counter, so clients can detect dropped rows.
+    pub index: u64,
+    // Stream time in seconds at the end of the analyzed window.
+    pub time: f64,
+    // Shared between the emitted row and the history, so snapshots taken under
+    // the analyzer lock copy pointers rather than values.
+    pub values: Arc<[f32]>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpectrogramSnapshot {
+    pub bands: Vec<Band>,
+    // Oldest row first.
+    pub rows: Vec<SpectrogramRow>,
+}
+
+pub struct Spectrogram {
+    config: AnalyzerConfig,
+    bands: Vec<Band>,
+    band_weights: Vec<BandWeights>,
+    weights_resolution: f32,
+    rows: VecDeque<SpectrogramRow>,
+    next_index: u64,
+}
+
+impl Spectrogram {
+    pub fn new(config: AnalyzerConfig) -> Self {
+        let bands =
+            config
+                .frequency_scale
+                .bands(config.spectrogram.bins, config.min_freq, config.max_freq);
+
+        Self {
+            rows: VecDeque::with_capacity(config.spectrogram.history),
+            bands,
+            band_weights: Vec::new(),
+            weights_resolution: 0.0,
+            next_index: 0,
+            config,
+        }
+    }
+
+    pub fn push(
+        &mut self,
+        magnitude: &[f32],
+        window: &WindowShape,
+        sample_rate: f32,
+        time: f64,
+    ) -> SpectrogramRow {
+        let freq_resolution = sample_rate / self.config.fft_size as f32;
+        if freq_resolution != self.weights_resolution {
+            self.band_weights = self
+                .bands
+                .iter()
+                .map(|band| BandWeights::new(band, freq_resolution, magnitude.len()))
+                .collect();
+            self.weights_resolution = freq_resolution;
+        }
+
+        let config = &self.config;
+        let values: Arc<[f32]> = self
+            .band_weights
+            .iter()
+            .map(|weights| {
+                let level = weights.level(magnitude, config.aggregation, window);
+                let db = 20.0 * level.max(1e-10).log10();
+                config
+                    .amplitude_mapping
+                    .map(db, config.floor_db, config.ceiling_db)
+            })
+            .collect();
+
+        let row = SpectrogramRow {
+            index: self.next_index,
+            time,
+            values,
+        };
+        self.next_index += 1;
+
+        if self.rows.len() == config.spectrogram.history {
+            self.rows.pop_front();
+        }
+        self.rows.push_back(row.clone());
+        row
+    }
+
+    pub fn snapshot(&self) -> SpectrogramSnapshot {
+        SpectrogramSnapshot {
+            bands: self.bands.clone(),
+            rows: self.rows.iter().cloned().collect(),
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+
+use crate::smoothing::smoothing_coefficient;
+
+const MAX_POINTS: usize = 8192;
+// Below this mean-square energy the correlation reads 0 rather than noise.
+const SILENCE_ENERGY: f32 = 1e-10;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct StereoConfig {
+    pub enabled: bool,
+    // How often a `stereo` event is emitted.
+    pub interval_ms: f32,
+    // Integration time of the correlation meter.
+    pub correlation_ms: f32,
+    // Goniometer points per event, decimated evenly over the interval.
+    pub points: usize,
+}
+
+impl Default for StereoConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_ms: 50.0,
+            correlation_ms: 300.0,
+            points: 512,
+        }
+    }
+}
+
+impl StereoConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(10.0..=1000.0).contains(&self.interval_ms) {
+            return Err(format!(
+                "stereo intervalMs must be between 10 and 1000, got {}",
+                self.interval_ms
+            ));
+        }
+        if !(self.correlation_ms > 0.0 && self.correlation_ms <= 10_000.0) {
+            return Err(format!(
+                "stereo correlationMs must be in (0, 10000], got {}",
+                self.correlation_ms
+            ));
+        }
+        if self.points == 0 || self.points > MAX_POINTS {
+            return Err(format!(
+                "stereo points must be between 1 and {}, got {}",
+                MAX_POINTS, self.points
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StereoFrame {
+    pub time: f64,
+    // +1 for mono-compatible, 0 for uncorrelated, -1 for phase-inverted.
+    pub correlation: f32,
+    // Goniometer (x, y) points: x = (R - L) / sqrt(2) so left leans negative,
+    // y = (L + R) / sqrt(2).
+    pub points: Vec<(f32, f32)>,
+}
+
+pub struct StereoMeter {
+    config: StereoConfig,
+    sample_rate: f32,
+    coeff: f32,
+    interval: usize,
+    stride: usize,
+    pos: usize,
+    // Exponential averages of L*R, L^2 and R^2.
+    product: f32,
+    left_energy: f32,
+    right_energy: f32,
+    points: Vec<(f32, f32)>,
+}
+
+impl StereoMeter {
+    pub fn new(config: StereoConfig) -> Self {
+        Self {
+            points: Vec::with_capacity(config.points),
+            config,
+            sample_rate: 0.0,
+            coeff: 0.0,
+            interval: 0,
+            stride: 1,
+            pos: 0,
+            product: 0.0,
+            left_energy: 0.0,
+            right_energy: 0.0,
+        }
+    }
+
+    fn configure(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.coeff = smoothing_coefficient(self.config.correlation_ms, 1000.0 / sample_rate);
+        self.interval = ((self.config.interval_ms / 1000.0 * sample_rate).round() as usize).max(1);
+        self.stride = self.interval.div_ceil(self.config.points);
+        self.pos = 0;
+        self.points.clear();
+    }
+
+    // Feeds one interleaved sample frame, reading the first two channels.
+    // Mono input produces nothing.
+    pub fn push(&mut self, frame: &[f32], sample_rate: f32, time: f64) -> Option<StereoFrame> {
+        let [left, right, ..] = *frame else {
+            return None;
+        };
+        if sample_rate != self.sample_rate {
+            self.configure(sample_rate);
+        }
+
+        self.product += (left * right - self.product) * self.coeff;
+        self.left_energy += (left * left - self.left_energy) * self.coeff;
+        self.right_energy += (right * right - self.right_energy) * self.coeff;
+
+        if self.pos.is_multiple_of(self.stride) {
+            let scale = std::f32::consts::FRAC_1_SQRT_2;
+            self.points
+                .push(((right - left) * scale, (left + right) * scale));
+        }
+
+        self.pos += 1;
+        if self.pos < self.interval {
+            return None;
+        }
+        self.pos = 0;
+
+        let energy = (self.left_energy * self.right_energy).sqrt();
+        let correlation = if energy > SILENCE_ENERGY {
+            (self.product / energy).clamp(-1.0, 1.0)
+        } else {
+            0.0
+        };
+
+        Some(StereoFrame {
+            time,
+            correlation,
+            points: std::mem::replace(&mut self.points, Vec::with_capacity(self.config.points)),
+        })
+    }
+}
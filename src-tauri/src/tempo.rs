@@ -0,0 +1,304 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+// Centre and width (in octaves) of the log-Gaussian tempo prior, which
+// resolves half/double-tempo ambiguity towards typical dance tempos.
+const PRIOR_BPM: f32 = 120.0;
+const PRIOR_OCTAVES: f32 = 1.0;
+// Width (standard deviation) of the Gaussian the onset envelope is smoothed
+// with, at least one frame. Without it a beat period that isn't a whole number
+// of frames splits its pulses between neighbouring lags.
+const SMOOTHING_MS: f32 = 20.0;
+// Spacing of the candidate beat periods, in frames.
+const PERIOD_STEP: f32 = 0.05;
+// A faster periodicity scoring at least this fraction of the best one is
+// taken as the beat.
+const FASTER_PEAK_RATIO: f32 = 0.8;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TempoConfig {
+    pub enabled: bool,
+    pub min_bpm: f32,
+    pub max_bpm: f32,
+    // Length of onset envelope analyzed for periodicity.
+    pub window_ms: f32,
+    pub beats_per_bar: u32,
+}
+
+impl Default for TempoConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_bpm: 60.0,
+            max_bpm: 200.0,
+            window_ms: 8000.0,
+            beats_per_bar: 4,
+        }
+    }
+}
+
+impl TempoConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.min_bpm >= 20.0 && self.max_bpm > self.min_bpm && self.max_bpm <= 400.0) {
+            return Err(format!(
+                "tempo range must satisfy 20 <= minBpm < maxBpm <= 400, got {} - {}",
+                self.min_bpm, self.max_bpm
+            ));
+        }
+        // At least two periods of the slowest tempo, plus room for the
+        // double-period comparison.
+        let min_window = 4.0 * 60_000.0 / self.min_bpm;
+        if !(self.window_ms >= min_window && self.window_ms <= 30_000.0) {
+            return Err(format!(
+                "tempo windowMs must be between {} and 30000, got {}",
+                min_window, self.window_ms
+            ));
+        }
+        if !(1..=16).contains(&self.beats_per_bar) {
+            return Err(format!(
+                "tempo beatsPerBar must be between 1 and 16, got {}",
+                self.beats_per_bar
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TempoFrame {
+    pub time: f64,
+    // None until enough envelope has been collected.
+    pub bpm: Option<f32>,
+    pub confidence: f32,
+    // Position within the current beat, 0 at the beat and rising towards 1.
+    pub phase: f32,
+    // Zero-based beat within the bar; 0 is the estimated downbeat.
+    pub beat_in_bar: u32,
+}
+
+pub struct TempoTracker {
+    config: TempoConfig,
+    envelope: VecDeque<f32>,
+}
+
+impl TempoTracker {
+    pub fn new(config: TempoConfig) -> Self {
+        Self {
+            config,
+            envelope: VecDeque::new(),
+        }
+    }
+
+    // Gaussian smoothing; near the ends the kernel is cut off and renormalized.
+    fn smooth(values: &[f32], sigma: f32) -> Vec<f32> {
+        let radius = (3.0 * sigma).ceil() as usize;
+        let kernel: Vec<f32> = (0..=2 * radius)
+            .map(|i| {
+                let x = (i as f32 - radius as f32) / sigma;
+                (-0.5 * x * x).exp()
+            })
+            .collect();
+        (0..values.len())
+            .map(|i| {
+                let start = i.saturating_sub(radius);
+                let end = (i + radius + 1).min(values.len());
+                let taps = &kernel[start + radius - i..end + radius - i];
+                let sum: f32 = taps
+                    .iter()
+                    .zip(&values[start..end])
+                    .map(|(k, v)| k * v)
+                    .sum();
+                sum / taps.iter().sum::<f32>()
+            })
+            .collect()
+    }
+
+    // Sums the envelope at `offset` frames back and every `period` before it.
+    fn comb(envelope: &[f32], offset: f32, period: f32) -> f32 {
+        let last = envelope.len() as f32 - 1.0;
+        let mut sum = 0.0;
+        let mut back = offset;
+        while back <= last {
+            sum += envelope[(last - back).round() as usize];
+            back += period;
+        }
+        sum
+    }
+
+    // Feeds one onset-envelope value per analysis frame.
+    pub fn update(&mut self, flux: f32, frame_ms: f32, time: f64) -> TempoFrame {
+        let max_len = (self.config.window_ms / frame_ms).ceil() as usize;
+        while self.envelope.len() >= max_len.max(1) {
+            self.envelope.pop_front();
+        }
+        self.envelope.push_back(flux);
+
+        let mut frame = TempoFrame {
+            time,
+            bpm: None,
+            confidence: 0.0,
+            phase: 0.0,
+            beat_in_bar: 0,
+        };
+
+        let frames_per_min = 60_000.0 / frame_ms;
+        let min_period = frames_per_min / self.config.max_bpm;
+        let max_period = frames_per_min / self.config.min_bpm;
+        let max_lag = max_period.ceil() as usize;
+        let len = self.envelope.len();
+        if len < 2 * max_lag + 1 || len * 2 < max_len {
+            return frame;
+        }
+
+        let raw: Vec<f32> = self.envelope.iter().copied().collect();
+        let smoothed = Self::smooth(&raw, (SMOOTHING_MS / frame_ms).max(1.0));
+        let mean = smoothed.iter().sum::<f32>() / len as f32;
+        let envelope: Vec<f32> = smoothed.iter().map(|v| v - mean).collect();
+        // Lags up to half the envelope, and at least two of the slowest periods.
+        let max_comb_lag = (len / 2).max(2 * max_lag).min(len - 1);
+        let autocorr: Vec<f32> = (0..=max_comb_lag)
+            .map(|lag| {
+                envelope[lag..]
+                    .iter()
+                    .zip(&envelope)
+                    .map(|(a, b)| a * b)
+                    .sum::<f32>()
+                    / (len - lag) as f32
+            })
+            .collect();
+        let energy = autocorr[0];
+        if energy <= 1e-12 {
+            return frame;
+        }
+        // Autocorrelation at a fractional lag.
+        let at = |lag: f32| -> f32 {
+            let k = lag.floor() as usize;
+            let frac = lag - k as f32;
+            match (autocorr.get(k), autocorr.get(k + 1)) {
+                (Some(a), Some(b)) => a * (1.0 - frac) + b * frac,
+                (Some(a), None) => *a,
+                _ => 0.0,
+            }
+        };
+
+        // Comb over the autocorrelation: each candidate period averages its
+        // multiples, which pins the period well below a frame.
+        let candidates = ((max_period - min_period) / PERIOD_STEP).floor() as usize;
+        let scores: Vec<f32> = (0..=candidates)
+            .map(|i| {
+                let period = min_period + i as f32 * PERIOD_STEP;
+                let teeth = ((max_comb_lag as f32 / period) as usize).max(1);
+                let comb: f32 = (1..=teeth).map(|k| at(k as f32 * period)).sum();
+                let octaves = (frames_per_min / period / PRIOR_BPM).log2() / PRIOR_OCTAVES;
+                let prior = (-0.5 * octaves * octaves).exp();
+                (comb / teeth as f32).max(0.0) * prior
+            })
+            .collect();
+        // Multiples of the beat period score as well as the period itself, so
+        // the fastest peak close to the best one is the beat.
+        let best = scores.iter().fold(0.0f32, |acc, &v| acc.max(v));
+        let (index, &peak) = scores
+            .iter()
+            .enumerate()
+            .find(|&(i, &v)| {
+                v >= FASTER_PEAK_RATIO * best
+                    && (i == 0 || v >= scores[i - 1])
+                    && scores.get(i + 1).is_none_or(|&next| v >= next)
+            })
+            .unwrap();
+        let period = min_period + index as f32 * PERIOD_STEP;
+        if peak <= 0.0 {
+            return frame;
+        }
+
+        // Beat phase: the offset whose pulse train best lines up with the
+        // onsets, counted back from the newest frame.
+        let offsets = period.round() as usize;
+        let offset = (0..offsets)
+            .max_by(|&a, &b| {
+                Self::comb(&envelope, a as f32, period)
+                    .total_cmp(&Self::comb(&envelope, b as f32, period))
+            })
+            .unwrap_or(0);
+
+        // Downbeat: the beat whose bar-spaced pulse train is strongest.
+        let beats_per_bar = self.config.beats_per_bar;
+        let bar = period * beats_per_bar as f32;
+        let beat_in_bar = (0..beats_per_bar)
+            .max_by(|&a, &b| {
+                let sa = Self::comb(&envelope, offset as f32 + a as f32 * period, bar);
+                let sb = Self::comb(&envelope, offset as f32 + b as f32 * period, bar);
+                sa.total_cmp(&sb)
+            })
+            .unwrap_or(0);
+
+        frame.bpm = Some(frames_per_min / period);
+        frame.confidence = (at(period) / energy).clamp(0.0, 1.0);
+        frame.phase = (offset as f32 / period).min(1.0);
+        frame.beat_in_bar = beat_in_bar;
+        frame
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analyzer::{Analyzer, AnalyzerEvent};
+    use crate::config::AnalyzerConfig;
+    use crate::tempo::TempoConfig;
+
+    const SAMPLE_RATE: f32 = 48000.0;
+
+    // Mono click track: a 5 ms decaying 2 kHz burst on every beat.
+    fn click_track(bpm: f32, seconds: f32) -> Vec<f32> {
+        let period = 60.0 / bpm * SAMPLE_RATE;
+        let click_len = (0.005 * SAMPLE_RATE) as usize;
+        let mut samples = vec![0.0; (seconds * SAMPLE_RATE) as usize];
+        let mut beat = 0.0;
+        while (beat as usize) < samples.len() {
+            let start = beat as usize;
+            for (n, sample) in samples[start..].iter_mut().take(click_len).enumerate() {
+                let t = n as f32 / SAMPLE_RATE;
+                *sample = 0.8 * (-t / 0.001).exp() * (std::f32::consts::TAU * 2000.0 * t).sin();
+            }
+            beat += period;
+        }
+        samples
+    }
+
+    fn estimate(bpm: f32, overlap: f32) -> f32 {
+        let config = AnalyzerConfig {
+            overlap,
+            tempo: TempoConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut analyzer = Analyzer::new(config);
+        let mut estimate = None;
+        analyzer.push(&click_track(bpm, 20.0), 1, SAMPLE_RATE, |event| {
+            if let AnalyzerEvent::Tempo(frame) = event {
+                estimate = frame.bpm.or(estimate);
+            }
+        });
+        estimate.unwrap()
+    }
+
+    #[test]
+    fn click_tracks_read_their_tempo() {
+        for bpm in [120.0, 174.0] {
+            for overlap in [0.0, 0.5, 0.75] {
+                let estimate = estimate(bpm, overlap);
+                assert!(
+                    (estimate - bpm).abs() <= 2.0,
+                    "{} BPM at overlap {} read {}",
+                    bpm,
+                    overlap,
+                    estimate
+                );
+            }
+        }
+    }
+}
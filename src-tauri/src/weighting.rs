@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+use crate::biquad::Biquad;
+
+const TILT_PIVOT_FREQ: f32 = 1000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrequencyWeighting {
+    A,
+    C,
+    #[default]
+    Z,
+    K,
+}
+
+// IEC 61672-1 A-weighting, 0 dB at 1 kHz.
+fn a_weighting(f: f64) -> f64 {
+    let f2 = f * f;
+    let ra = 12194.0f64.powi(2) * f2 * f2
+        / ((f2 + 20.6f64.powi(2))
+            * ((f2 + 107.7f64.powi(2)) * (f2 + 737.9f64.powi(2))).sqrt()
+            * (f2 + 12194.0f64.powi(2)));
+    20.0 * ra.log10() + 2.0
+}
+
+// IEC 61672-1 C-weighting, 0 dB at 1 kHz.
+fn c_weighting(f: f64) -> f64 {
+    let f2 = f * f;
+    let rc = 12194.0f64.powi(2) * f2 / ((f2 + 20.6f64.powi(2)) * (f2 + 12194.0f64.powi(2)));
+    20.0 * rc.log10() + 0.06
+}
+
+// ITU-R BS.1770 K-weighting, evaluated at its 48 kHz reference rate and
+// normalized to 0 dB at 1 kHz like the other curves.
+fn k_weighting(f: f64) -> f64 {
+    const REFERENCE_RATE: f64 = 48000.0;
+    let shelf = Biquad::k_shelf(REFERENCE_RATE);
+    let highpass = Biquad::k_highpass(REFERENCE_RATE);
+    let response = |f: f64| {
+        let f = f.min(REFERENCE_RATE / 2.0 - 1.0);
+        shelf.magnitude_db(f, REFERENCE_RATE) + highpass.magnitude_db(f, REFERENCE_RATE)
+    };
+    response(f) - response(1000.0)
+}
+
+impl FrequencyWeighting {
+    pub fn gain_db(&self, freq: f32) -> f32 {
+        let f = freq.max(1.0) as f64;
+        let gain = match self {
+            FrequencyWeighting::A => a_weighting(f),
+            FrequencyWeighting::C => c_weighting(f),
+            FrequencyWeighting::Z => 0.0,
+            FrequencyWeighting::K => k_weighting(f),
+        };
+        gain as f32
+    }
+}
+
+pub fn tilt_db(freq: f32, db_per_octave: f32) -> f32 {
+    db_per_octave * (freq.max(1.0) / TILT_PIVOT_FREQ).log2()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // IEC 61672-1 table values at the exact base-ten frequencies
+    // 1000 * 10^(n/10) for n = -20 (10 Hz) ..= 13 (20 kHz), rounded to 0.1 dB.
+    const A_TABLE: [f32; 34] = [
+        -70.4, -63.4, -56.7, -50.5, -44.7, -39.4, -34.6, -30.2, -26.2, -22.5, -19.1, -16.1, -13.4,
+        -10.9, -8.6, -6.6, -4.8, -3.2, -1.9, -0.8, 0.0, 0.6, 1.0, 1.2, 1.3, 1.2, 1.0, 0.5, -0.1,
+        -1.1, -2.5, -4.3, -6.6, -9.3,
+    ];
+    const C_TABLE: [f32; 34] = [
+        -14.3, -11.2, -8.5, -6.2, -4.4, -3.0, -2.0, -1.3, -0.8, -0.5, -0.3, -0.2, -0.1, 0.0, 0.0,
+        0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -0.1, -0.2, -0.3, -0.5, -0.8, -1.3, -2.0, -3.0, -4.4,
+        -6.2, -8.5, -11.2,
+    ];
+
+    #[test]
+    fn a_and_c_curves_match_iec_61672() {
+        for (i, (a, c)) in A_TABLE.iter().zip(&C_TABLE).enumerate() {
+            let freq = 1000.0 * 10f32.powf((i as f32 - 20.0) / 10.0);
+            let read_a = FrequencyWeighting::A.gain_db(freq);
+            let read_c = FrequencyWeighting::C.gain_db(freq);
+            assert!(
+                (read_a - a).abs() < 0.06,
+                "A at {} Hz read {}",
+                freq,
+                read_a
+            );
+            assert!(
+                (read_c - c).abs() < 0.06,
+                "C at {} Hz read {}",
+                freq,
+                read_c
+            );
+        }
+    }
+
+    #[test]
+    fn k_curve_matches_bs_1770() {
+        // The high shelf adds about 4 dB at high frequencies, 0.69 dB of which
+        // already applies at 1 kHz; the high-pass rolls off the bass.
+        let k = |freq: f32| FrequencyWeighting::K.gain_db(freq);
+        assert!(k(1000.0).abs() < 1e-3);
+        assert!((k(10000.0) - 3.31).abs() < 0.05, "read {}", k(10000.0));
+        assert!((k(100.0) + 1.83).abs() < 0.05, "read {}", k(100.0));
+        assert!(k(20.0) < -13.0, "read {}", k(20.0));
+    }
+
+    #[test]
+    fn z_is_flat_and_tilt_pivots_at_1_khz() {
+        for freq in [20.0, 1000.0, 20000.0] {
+            assert_eq!(FrequencyWeighting::Z.gain_db(freq), 0.0);
+        }
+        assert_eq!(tilt_db(1000.0, 3.0), 0.0);
+        assert!((tilt_db(4000.0, 3.0) - 6.0).abs() < 1e-4);
+        assert!((tilt_db(250.0, -4.5) - 9.0).abs() < 1e-4);
+    }
+}
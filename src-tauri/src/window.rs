@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum WindowFunction {
+    #[default]
+    Hann,
+    Hamming,
+    Blackman,
+    BlackmanHarris,
+    FlatTop,
+    Kaiser {
+        beta: f32,
+    },
+    Rectangular,
+}
+
+pub struct WindowShape {
+    pub coefficients: Vec<f32>,
+    // Mean of the coefficients; divides out the amplitude loss for tones.
+    pub coherent_gain: f32,
+    // Equivalent noise bandwidth in bins; divides out the power gain for noise.
+    pub noise_bandwidth: f32,
+}
+
+fn cosine_sum(size: usize, a: &[f32]) -> Vec<f32> {
+    let denom = (size - 1) as f32;
+    (0..size)
+        .map(|i| {
+            let x = 2.0 * PI * i as f32 / denom;
+            a.iter()
+                .enumerate()
+                .map(|(k, &ak)| {
+                    let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+                    sign * ak * (k as f32 * x).cos()
+                })
+                .sum()
+        })
+        .collect()
+}
+
+// Zeroth-order modified Bessel function of the first kind.
+fn bessel_i0(x: f32) -> f32 {
+    let half = x / 2.0;
+    let mut sum = 1.0f32;
+    let mut term = 1.0f32;
+    for k in 1..50 {
+        term *= (half / k as f32) * (half / k as f32);
+        sum += term;
+        if term < sum * 1e-8 {
+            break;
+        }
+    }
+    sum
+}
+
+fn kaiser(size: usize, beta: f32) -> Vec<f32> {
+    let denom = (size - 1) as f32;
+    let norm = bessel_i0(beta);
+    (0..size)
+        .map(|i| {
+            let r = 2.0 * i as f32 / denom - 1.0;
+            bessel_i0(beta * (1.0 - r * r).max(0.0).sqrt()) / norm
+        })
+        .collect()
+}
+
+impl WindowFunction {
+    pub fn validate(&self) -> Result<(), String> {
+        if let WindowFunction::Kaiser { beta } = *self {
+            if !(0.0..=50.0).contains(&beta) {
+                return Err(format!(
+                    "kaiser beta must be between 0 and 50, got {}",
+                    beta
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn build(&self, size: usize) -> WindowShape {
+        let coefficients = match *self {
+            WindowFunction::Hann => cosine_sum(size, &[0.5, 0.5]),
+            WindowFunction::Hamming => cosine_sum(size, &[0.54, 0.46]),
+            WindowFunction::Blackman => cosine_sum(size, &[0.42, 0.5, 0.08]),
+            WindowFunction::BlackmanHarris => {
+                cosine_sum(size, &[0.35875, 0.48829, 0.14128, 0.01168])
+            }
+            WindowFunction::FlatTop => cosine_sum(
+                size,
+                &[0.21557895, 0.41663158, 0.27726316, 0.08357895, 0.00694737],
+            ),
+            WindowFunction::Kaiser { beta } => kaiser(size, beta),
+            WindowFunction::Rectangular => vec![1.0; size],
+        };
+
+        let sum: f32 = coefficients.iter().sum();
+        let sum_sq: f32 = coefficients.iter().map(|w| w * w).sum();
+
+        WindowShape {
+            coherent_gain: sum / size as f32,
+            noise_bandwidth: size as f32 * sum_sq / (sum * sum),
+            coefficients,
+        }
+    }
+}
@@ -3,19 +3,37 @@ import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import "./App.css";
 
-const NUM_BARS = 64;
+const DEFAULT_NUM_BARS = 64;
+
+interface ChannelSpectrum {
+  label: string;
+  bars: number[];
+  peaks: number[];
+  gain: number;
+}
+
+interface SpectrumFrame {
+  channels: ChannelSpectrum[];
+}
 
 function App() {
   const canvasRef = useRef<HTMLCanvasElement>(null);
-  const barsRef = useRef<number[]>(new Array(NUM_BARS).fill(0));
-  const targetRef = useRef<number[]>(new Array(NUM_BARS).fill(0));
+  const barsRef = useRef<number[]>(new Array(DEFAULT_NUM_BARS).fill(0));
+  const targetRef = useRef<number[]>(new Array(DEFAULT_NUM_BARS).fill(0));
+  const peaksRef = useRef<number[]>(new Array(DEFAULT_NUM_BARS).fill(0));
   const frameRef = useRef<number>(0);
 
   useEffect(() => {
     invoke("start_audio_listener").catch(console.error);
 
-    const unlisten = listen<number[]>("audio-data", (e) => {
-      targetRef.current = e.payload;
+    const unlisten = listen<SpectrumFrame>("audio-data", (e) => {
+      const channel = e.payload.channels[0];
+      const bars = channel?.bars ?? [];
+      if (bars.length !== barsRef.current.length) {
+        barsRef.current = new Array(bars.length).fill(0);
+      }
+      targetRef.current = bars;
+      peaksRef.current = channel?.peaks ?? [];
     });
 
     const canvas = canvasRef.current!;
@@ -39,11 +57,12 @@ function App() {
 
       ctx.clearRect(0, 0, w, h);
 
+      const numBars = barsRef.current.length;
       const gap = 2;
-      const totalWidth = w - gap * (NUM_BARS + 1);
-      const barW = totalWidth / NUM_BARS;
+      const totalWidth = w - gap * (numBars + 1);
+      const barW = totalWidth / numBars;
 
-      for (let i = 0; i < NUM_BARS; i++) {
+      for (let i = 0; i < numBars; i++) {
         const target = targetRef.current[i] || 0;
         const current = barsRef.current[i];
 
@@ -74,6 +93,13 @@ function App() {
         ctx.beginPath();
         ctx.roundRect(x, y, barW, barH, [r, r, 0, 0]);
         ctx.fill();
+
+        const peak = Math.min(peaksRef.current[i] || 0, 1);
+        if (peak > 0) {
+          const peakY = h - Math.max(peak * h * 0.9, 2) - 3;
+          ctx.fillStyle = `hsla(${hue + 30}, ${sat}%, ${light + 25}%, 0.9)`;
+          ctx.fillRect(x, Math.min(peakY, y - 3), barW, 2);
+        }
       }
 
       frameRef.current = requestAnimationFrame(draw);